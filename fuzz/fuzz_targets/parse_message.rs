@@ -0,0 +1,64 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+//! Parse arbitrary bytes as messages of every protocol with a decoder and
+//! exercise the payload accessors.
+//!
+//! Run with `cargo +nightly fuzz run parse_message`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use netlink_rs::message::route::MessageType;
+use netlink_rs::message::{NetlinkMessageIterator, NetlinkPayload, NETLINK_MESSAGE_MAXIMUM_SIZE};
+use netlink_rs::socket::NetlinkProtocol;
+
+fuzz_target!(|bytes: &[u8]| {
+    for protocol in [
+        NetlinkProtocol::Route,
+        NetlinkProtocol::Generic,
+        NetlinkProtocol::SockDiag,
+        NetlinkProtocol::Audit,
+    ] {
+        for message in NetlinkMessageIterator::with_protocol(bytes, protocol).flatten() {
+            let _ = message.category();
+            let _ = message.validate();
+            match &message.payload {
+                NetlinkPayload::Route(MessageType::Link(link)) => {
+                    let _ = (link.name(), link.alias(), link.vf_info(), link.map());
+                    let _ = (link.vlan_info(), link.bond_info(), link.wireless_events());
+                }
+                NetlinkPayload::Route(MessageType::Route(route)) => {
+                    let _ = (route.encap(), route.preference(), route.realm());
+                }
+                NetlinkPayload::Route(MessageType::NextHop(nexthop)) => {
+                    let _ = (nexthop.gateway(), nexthop.group());
+                }
+                _ => (),
+            }
+
+            let mut buffer = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+            let _ = message.to_array(&mut buffer);
+        }
+    }
+});
@@ -0,0 +1,387 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::message::{
+    NetlinkHeader, NetlinkMessage, NetlinkMessageIterator, NetlinkParseError, NetlinkPayload,
+    PortId, NETLINK_MESSAGE_MAXIMUM_SIZE, NLMSG_DONE,
+};
+use crate::socket::{NetlinkProtocol, NetlinkSocket, Transport};
+use crate::{Error, Result};
+use std::io::{self, ErrorKind};
+use std::mem;
+
+/// Netlink request/reply handling on top of a [`NetlinkSocket`] (or any
+/// other [`Transport`]).
+///
+/// The connection assigns the message sequence numbers and matches the
+/// replies with the requests.
+pub struct NetlinkConnection<T: Transport = NetlinkSocket> {
+    transport: T,
+    sequence: u32,
+    protocol: NetlinkProtocol,
+}
+
+impl NetlinkConnection<NetlinkSocket> {
+    /// Create a connection using a new socket for `protocol` with a kernel
+    /// assigned port ID and no notification groups.
+    pub fn new(protocol: NetlinkProtocol) -> Result<NetlinkConnection> {
+        Ok(NetlinkConnection::from_socket(NetlinkSocket::bind(
+            protocol,
+            PortId::auto(),
+            0,
+        )?))
+    }
+
+    /// Create a connection using an already bound socket, replies are
+    /// parsed according to the socket protocol.
+    pub fn from_socket(socket: NetlinkSocket) -> NetlinkConnection {
+        let protocol = socket.protocol();
+        let mut connection = NetlinkConnection::from_transport(socket);
+        connection.set_protocol(protocol);
+        connection
+    }
+}
+
+impl<T: Transport> NetlinkConnection<T> {
+    /// Create a connection exchanging messages through `transport`.
+    ///
+    /// Replies are parsed as `NETLINK_ROUTE` messages, use
+    /// [`NetlinkConnection::set_protocol`] for other protocols.
+    pub fn from_transport(transport: T) -> NetlinkConnection<T> {
+        NetlinkConnection {
+            transport,
+            sequence: 0,
+            protocol: NetlinkProtocol::Route,
+        }
+    }
+
+    /// Set the protocol used to parse the replies.
+    pub fn set_protocol(&mut self, protocol: NetlinkProtocol) {
+        self.protocol = protocol;
+    }
+
+    /// Assign the next sequence number to `message` and serialize it.
+    ///
+    /// Returns the message bytes and the assigned sequence number, used to
+    /// correlate the replies when sending through other means (e.g.
+    /// [`NetlinkConnection::execute_raw`]).
+    pub fn serialize(&mut self, mut message: NetlinkMessage) -> Result<(Vec<u8>, u32)> {
+        self.sequence = self.sequence.wrapping_add(1);
+        message.header.sequence = self.sequence;
+        message
+            .validate()
+            .map_err(|error| io::Error::new(ErrorKind::InvalidInput, error))?;
+
+        let mut buffer = vec![0u8; message.header.length as usize];
+        let length = message.to_array(&mut buffer);
+        buffer.truncate(length);
+        Ok((buffer, self.sequence))
+    }
+
+    fn send(&mut self, mut message: NetlinkMessage, flags: i32) -> Result<u32> {
+        message.header.flags |= flags as u16;
+        let (buffer, sequence) = self.serialize(message)?;
+        self.transport.send(&buffer)?;
+        Ok(sequence)
+    }
+
+    /// Receive the replies to request `sequence`, when `acknowledged` is
+    /// set the replies end only with the acknowledgment.
+    fn receive<F>(&mut self, sequence: u32, acknowledged: bool, mut callback: F) -> Result<()>
+    where
+        F: FnMut(NetlinkMessage),
+    {
+        let mut buffer = vec![0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        loop {
+            let length = self.transport.recv(&mut buffer)?;
+            for message in NetlinkMessageIterator::with_protocol(&buffer[..length], self.protocol) {
+                let message = message?;
+                if message.header.sequence != sequence {
+                    continue;
+                }
+                if message.header.kind == NLMSG_DONE {
+                    return Ok(());
+                }
+
+                let multipart = (message.header.flags & libc::NLM_F_MULTI as u16) != 0;
+                match message.payload {
+                    NetlinkPayload::Error(error) if error.error != 0 => {
+                        return Err(io::Error::from_raw_os_error(-error.error).into())
+                    }
+                    NetlinkPayload::Error(_) => return Ok(()),
+                    _ => callback(message),
+                }
+                if !multipart && !acknowledged {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Send `message` requesting an acknowledgment (`NLM_F_ACK`) and wait
+    /// for it.
+    ///
+    /// Kernel errors are returned as [`Error::Io`] with the reported
+    /// `errno` (see [`Error::raw_os_error`]), invalid messages (see
+    /// [`NetlinkMessage::validate`]) are rejected with
+    /// [`std::io::ErrorKind::InvalidInput`] before sending and malformed
+    /// replies fail with [`Error::Parse`].
+    pub fn execute(&mut self, message: NetlinkMessage) -> Result<()> {
+        self.execute_echo(message, |_| ())
+    }
+
+    /// Same as [`NetlinkConnection::execute`], but calls `callback` with
+    /// the objects echoed by the kernel before the acknowledgment (requests
+    /// with `NLM_F_ECHO`, e.g. [`crate::message::route_builder::LinkBuilder::echo`]).
+    ///
+    /// Allows learning kernel assigned values, like the index of a new
+    /// interface.
+    pub fn execute_echo<F>(&mut self, message: NetlinkMessage, callback: F) -> Result<()>
+    where
+        F: FnMut(NetlinkMessage),
+    {
+        let sequence = self.send(message, libc::NLM_F_REQUEST | libc::NLM_F_ACK)?;
+        self.receive(sequence, true, callback)
+    }
+
+    /// Send the already serialized message in `bytes` and wait for the
+    /// acknowledgment matching its sequence number.
+    ///
+    /// The message is sent as is: it must request the acknowledgment
+    /// (`NLM_F_REQUEST | NLM_F_ACK`) and use a sequence number not used by
+    /// this connection (e.g. above `u32::MAX / 2`).
+    pub fn execute_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        if bytes.len() < mem::size_of::<NetlinkHeader>() {
+            return Err(Error::Io(io::Error::new(
+                ErrorKind::InvalidInput,
+                NetlinkParseError::MessageTooSmall,
+            )));
+        }
+
+        let sequence = u32::from_ne_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+        self.transport.send(bytes)?;
+        self.receive(sequence, true, |_| ())
+    }
+
+    /// Send `message` and call `callback` for every reply until the end of
+    /// the multipart reply (`NLMSG_DONE`) or the first reply otherwise.
+    pub fn request<F>(&mut self, message: NetlinkMessage, callback: F) -> Result<()>
+    where
+        F: FnMut(NetlinkMessage),
+    {
+        let sequence = self.send(message, libc::NLM_F_REQUEST)?;
+        self.receive(sequence, false, callback)
+    }
+}
+
+#[cfg(test)]
+mod connection_test {
+    use super::*;
+    use crate::message::route::{family, MessageType};
+    use crate::message::route_builder::{get_links_request, LinkBuilder};
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// Transport recording the sent messages and replaying canned replies.
+    struct MockTransport {
+        sent: RefCell<Vec<Vec<u8>>>,
+        replies: RefCell<VecDeque<Vec<u8>>>,
+    }
+
+    impl MockTransport {
+        fn new(replies: Vec<Vec<u8>>) -> MockTransport {
+            MockTransport {
+                sent: RefCell::new(vec![]),
+                replies: RefCell::new(replies.into()),
+            }
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn send(&self, buffer: &[u8]) -> io::Result<usize> {
+            self.sent.borrow_mut().push(buffer.to_vec());
+            Ok(buffer.len())
+        }
+
+        fn recv(&self, buffer: &mut [u8]) -> io::Result<usize> {
+            let reply = self
+                .replies
+                .borrow_mut()
+                .pop_front()
+                .ok_or_else(|| io::Error::from(ErrorKind::WouldBlock))?;
+            buffer[..reply.len()].copy_from_slice(&reply);
+            Ok(reply.len())
+        }
+    }
+
+    fn message(kind: u16, flags: i32, sequence: u32, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + payload.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&(flags as u16).to_ne_bytes());
+        bytes.extend_from_slice(&sequence.to_ne_bytes());
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn link(index: i32) -> Vec<u8> {
+        let mut bytes = vec![0u8; 16];
+        bytes[4..8].copy_from_slice(&index.to_ne_bytes());
+        bytes
+    }
+
+    #[test]
+    fn request_multipart() {
+        // First datagram: one reply to an old request and two links.
+        let mut first = message(libc::RTM_NEWLINK, libc::NLM_F_MULTI, 7, &link(9));
+        first.extend(message(libc::RTM_NEWLINK, libc::NLM_F_MULTI, 1, &link(1)));
+        first.extend(message(libc::RTM_NEWLINK, libc::NLM_F_MULTI, 1, &link(2)));
+        let done = message(NLMSG_DONE, libc::NLM_F_MULTI, 1, &0i32.to_ne_bytes());
+        let mut connection =
+            NetlinkConnection::from_transport(MockTransport::new(vec![first, done]));
+
+        let mut indexes = vec![];
+        connection
+            .request(get_links_request().build(), |message| {
+                if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                    indexes.push(link.message.index);
+                }
+            })
+            .unwrap();
+        assert_eq!(indexes, vec![1, 2]);
+
+        let sent = connection.transport.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        let request = NetlinkMessage::from(&sent[0]).unwrap();
+        assert_eq!(request.header.kind, libc::RTM_GETLINK);
+        assert_eq!(request.header.sequence, 1);
+    }
+
+    #[test]
+    fn serialize_sequence() {
+        let mut connection = NetlinkConnection::from_transport(MockTransport::new(vec![]));
+        let (_, first) = connection.serialize(get_links_request().build()).unwrap();
+        let (bytes, sequence) = connection
+            .serialize(LinkBuilder::set_link(5).up().build())
+            .unwrap();
+        assert_eq!(sequence, first + 1);
+
+        let message = NetlinkMessage::from(&bytes).unwrap();
+        assert_eq!(message.header.sequence, sequence);
+        assert_eq!(message.header.length as usize, bytes.len());
+        assert!(connection.transport.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn build_for() {
+        let mut connection = NetlinkConnection::from_transport(MockTransport::new(vec![]));
+        let (_, first) = get_links_request().build_for(&mut connection).unwrap();
+        let (bytes, sequence) = LinkBuilder::set_link(5)
+            .up()
+            .build_for(&mut connection)
+            .unwrap();
+        assert_eq!(sequence, first + 1);
+        assert_eq!(
+            u32::from_ne_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            sequence
+        );
+    }
+
+    #[test]
+    fn execute_error() {
+        let mut error = (-libc::ENODEV).to_ne_bytes().to_vec();
+        error.extend(message(libc::RTM_SETLINK, libc::NLM_F_REQUEST, 1, &[]));
+        let mut connection = NetlinkConnection::from_transport(MockTransport::new(vec![message(
+            libc::NLMSG_ERROR as u16,
+            0,
+            1,
+            &error,
+        )]));
+
+        let error = connection
+            .execute(LinkBuilder::set_link(5).up().build())
+            .unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::ENODEV));
+    }
+
+    #[test]
+    fn request_error_kinds() {
+        // Reply header claims more bytes than received.
+        let mut truncated = message(libc::RTM_NEWLINK, 0, 1, &link(1));
+        truncated[..4].copy_from_slice(&64u32.to_ne_bytes());
+        let mut connection = NetlinkConnection::from_transport(MockTransport::new(vec![truncated]));
+        let error = connection
+            .request(get_links_request().build(), |_| ())
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse(NetlinkParseError::MessageIncomplete)
+        ));
+
+        // No reply: the transport fails.
+        let error = connection
+            .request(get_links_request().build(), |_| ())
+            .unwrap_err();
+        assert!(matches!(&error, Error::Io(error) if error.kind() == ErrorKind::WouldBlock));
+    }
+
+    #[test]
+    fn execute_raw() {
+        // `RTM_NEWROUTE` for 192.0.2.0/24 via loopback.
+        let mut route = vec![family::INET, 24, 0, 0];
+        route.extend_from_slice(&[
+            libc::RT_TABLE_MAIN,
+            libc::RTPROT_STATIC,
+            0,
+            libc::RTN_UNICAST,
+        ]);
+        route.extend_from_slice(&0u32.to_ne_bytes());
+        route.extend_from_slice(&8u16.to_ne_bytes());
+        route.extend_from_slice(&libc::RTA_DST.to_ne_bytes());
+        route.extend_from_slice(&[192, 0, 2, 0]);
+        route.extend_from_slice(&8u16.to_ne_bytes());
+        route.extend_from_slice(&libc::RTA_OIF.to_ne_bytes());
+        route.extend_from_slice(&1u32.to_ne_bytes());
+        let request = message(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_REQUEST | libc::NLM_F_ACK | libc::NLM_F_CREATE,
+            0x8000_0001,
+            &route,
+        );
+
+        let mut ack = 0i32.to_ne_bytes().to_vec();
+        ack.extend_from_slice(&request[..16]);
+        let mut connection = NetlinkConnection::from_transport(MockTransport::new(vec![
+            message(libc::RTM_NEWLINK, 0, 0, &link(1)),
+            message(libc::NLMSG_ERROR as u16, 0, 0x8000_0001, &ack),
+        ]));
+        connection.execute_raw(&request).unwrap();
+        assert_eq!(*connection.transport.sent.borrow(), vec![request.clone()]);
+
+        assert_eq!(
+            connection.execute_raw(&request[..15]).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::message::NetlinkParseError;
+use std::fmt;
+use std::io;
+
+/// Crate error: operating system (socket or kernel reported) failures and
+/// message parsing failures.
+#[derive(Debug)]
+pub enum Error {
+    /// Socket failure or error reported by the kernel (see
+    /// [`Error::raw_os_error`]).
+    Io(io::Error),
+    /// Malformed message received.
+    Parse(NetlinkParseError),
+}
+
+/// Result of the crate high level helpers.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// `errno` of operating system and kernel reported failures.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self {
+            Error::Io(error) => error.raw_os_error(),
+            Error::Parse(_) => None,
+        }
+    }
+
+    /// Error category, parse failures are `InvalidData`.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Io(error) => error.kind(),
+            Error::Parse(_) => io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => error.fmt(formatter),
+            Error::Parse(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl From<NetlinkParseError> for Error {
+    fn from(error: NetlinkParseError) -> Error {
+        Error::Parse(error)
+    }
+}
+
+/// For callers still working with [`std::io::Result`].
+impl From<Error> for io::Error {
+    fn from(error: Error) -> io::Error {
+        match error {
+            Error::Io(error) => error,
+            Error::Parse(error) => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod error_test {
+    use super::*;
+    use crate::message::NetlinkMessage;
+
+    fn parse(bytes: &[u8]) -> Result<u16> {
+        Ok(NetlinkMessage::from(bytes)?.header.kind)
+    }
+
+    fn open(path: &str) -> Result<()> {
+        std::fs::File::open(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn question_mark() {
+        let error = parse(&[0u8; 15]).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Parse(NetlinkParseError::MessageIncomplete)
+        ));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.raw_os_error(), None);
+
+        let error = open("/nonexistent").unwrap_err();
+        assert!(matches!(error, Error::Io(_)));
+        assert_eq!(error.raw_os_error(), Some(libc::ENOENT));
+        assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotFound);
+    }
+}
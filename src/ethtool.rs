@@ -0,0 +1,260 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::connection::NetlinkConnection;
+use crate::message::generic::{get_family_request, GenericBuilder, GenericMessage};
+use crate::message::route_attribute::{nested_payload, Attribute, AttributeValue};
+use crate::message::NetlinkPayload;
+use crate::socket::{NetlinkProtocol, NetlinkSocket};
+use crate::Result;
+use std::io::{Error, ErrorKind};
+
+/// Ethtool generic netlink family name and interface version
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool_netlink.h)).
+pub const FAMILY_NAME: &str = "ethtool";
+pub const FAMILY_VERSION: u8 = 1;
+
+/// Ethtool commands
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool_netlink.h)).
+pub mod ethtool_message {
+    pub const LINKMODES_GET: u8 = 4;
+    pub const LINKMODES_GET_REPLY: u8 = 4;
+}
+
+/// Request header attributes (`ETHTOOL_A_HEADER_*`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool_netlink.h)).
+pub mod header_attribute {
+    pub const DEV_INDEX: u16 = 1;
+    pub const DEV_NAME: u16 = 2;
+    pub const FLAGS: u16 = 3;
+}
+
+/// Link modes attributes (`ETHTOOL_A_LINKMODES_*`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool_netlink.h)).
+pub mod linkmodes_attribute {
+    pub const HEADER: u16 = 1;
+    pub const AUTONEG: u16 = 2;
+    pub const OURS: u16 = 3;
+    pub const PEER: u16 = 4;
+    pub const SPEED: u16 = 5;
+    pub const DUPLEX: u16 = 6;
+}
+
+/// Speed reported when the link is down or the driver doesn't know it
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool.h)).
+pub const SPEED_UNKNOWN: u32 = u32::MAX;
+
+/// Link duplex mode
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ethtool.h)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    Half,
+    Full,
+}
+
+impl Duplex {
+    /// Duplex from the `DUPLEX_*` value, `None` for `DUPLEX_UNKNOWN`.
+    pub fn from_raw(duplex: u8) -> Option<Duplex> {
+        match duplex {
+            0 => Some(Duplex::Half),
+            1 => Some(Duplex::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Link speed and duplex reported by `ETHTOOL_MSG_LINKMODES_GET`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkModes {
+    /// Speed in Mb/s, `None` when unknown (e.g. link down).
+    pub speed: Option<u32>,
+    /// Duplex mode, `None` when unknown.
+    pub duplex: Option<Duplex>,
+    /// Auto negotiation enabled.
+    pub autoneg: Option<bool>,
+}
+
+impl LinkModes {
+    /// Decode the link modes reply attributes.
+    pub fn from(message: &GenericMessage) -> LinkModes {
+        let mut modes = LinkModes::default();
+        for attribute in &message.attributes {
+            let Attribute::Unknown(attribute) = attribute else {
+                continue;
+            };
+            match (attribute.kind, attribute.value.as_slice()) {
+                (linkmodes_attribute::SPEED, &[a, b, c, d]) => {
+                    let speed = u32::from_ne_bytes([a, b, c, d]);
+                    modes.speed = (speed != SPEED_UNKNOWN).then_some(speed);
+                }
+                (linkmodes_attribute::DUPLEX, &[duplex]) => modes.duplex = Duplex::from_raw(duplex),
+                (linkmodes_attribute::AUTONEG, &[autoneg]) => modes.autoneg = Some(autoneg != 0),
+                _ => (),
+            }
+        }
+        modes
+    }
+}
+
+/// Build the link modes request for the interface `ifindex`, `family` is
+/// the ethtool family ID.
+pub fn get_link_modes_request(family: u16, ifindex: u32) -> GenericBuilder {
+    let header = nested_payload(&[Attribute::U32(AttributeValue::<u32> {
+        length: 0,
+        kind: header_attribute::DEV_INDEX,
+        value: ifindex,
+    })]);
+
+    GenericBuilder::new(family, ethtool_message::LINKMODES_GET, FAMILY_VERSION).attribute(
+        Attribute::Nested(AttributeValue::<Vec<u8>> {
+            length: 0,
+            kind: linkmodes_attribute::HEADER | libc::NLA_F_NESTED as u16,
+            value: header,
+        }),
+    )
+}
+
+/// Resolve the ethtool family ID, fails with `ENOENT` when the kernel
+/// doesn't have ethtool netlink support.
+fn family_id(connection: &mut NetlinkConnection<&NetlinkSocket>) -> Result<u16> {
+    let mut family = None;
+    connection.request(get_family_request(FAMILY_NAME).build(), |message| {
+        if let NetlinkPayload::Generic(reply) = message.payload {
+            family = reply.family_id();
+        }
+    })?;
+
+    family.ok_or_else(|| Error::new(ErrorKind::InvalidData, "family ID missing in reply").into())
+}
+
+/// Query the speed and duplex of the interface `ifindex`, `socket` must
+/// be a generic netlink socket ([`NetlinkProtocol::Generic`]).
+///
+/// Drivers without link settings (e.g. loopback) fail with `EOPNOTSUPP`.
+pub fn get_link_modes(socket: &NetlinkSocket, ifindex: u32) -> Result<LinkModes> {
+    let mut connection = NetlinkConnection::from_transport(socket);
+    connection.set_protocol(NetlinkProtocol::Generic);
+
+    let family = family_id(&mut connection)?;
+    let mut modes = None;
+    connection.request(get_link_modes_request(family, ifindex).build(), |message| {
+        if let NetlinkPayload::Generic(reply) = message.payload {
+            if reply.command == ethtool_message::LINKMODES_GET_REPLY {
+                modes = Some(LinkModes::from(&reply));
+            }
+        }
+    })?;
+
+    modes.ok_or_else(|| Error::new(ErrorKind::InvalidData, "link modes missing in reply").into())
+}
+
+#[cfg(test)]
+mod ethtool_test {
+    use super::*;
+    use crate::message::route_attribute::visit_attributes;
+    use crate::message::{NetlinkMessage, PortId, NETLINK_MESSAGE_MAXIMUM_SIZE};
+
+    #[test]
+    fn link_modes_request() {
+        let message = get_link_modes_request(20, 3).build();
+        assert_eq!(message.header.kind, 20);
+        assert_eq!(message.header.length, 16 + 4 + 12);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        match NetlinkMessage::from_generic(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Generic(generic),
+                ..
+            }) => {
+                assert_eq!(generic.command, ethtool_message::LINKMODES_GET);
+                assert_eq!(generic.version, FAMILY_VERSION);
+                let header = generic
+                    .attribute(linkmodes_attribute::HEADER | libc::NLA_F_NESTED as u16)
+                    .unwrap();
+                let mut index = None;
+                visit_attributes(header, |kind, payload| {
+                    if kind == header_attribute::DEV_INDEX {
+                        index = Some(u32::from_ne_bytes(payload.try_into().unwrap()));
+                    }
+                })
+                .unwrap();
+                assert_eq!(index, Some(3));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn link_modes_reply() {
+        let unknown = |kind, value: Vec<u8>| {
+            Attribute::Unknown(AttributeValue::<Vec<u8>> {
+                length: 0,
+                kind,
+                value,
+            })
+        };
+        let reply = GenericMessage {
+            command: ethtool_message::LINKMODES_GET_REPLY,
+            version: FAMILY_VERSION,
+            attributes: vec![
+                unknown(linkmodes_attribute::AUTONEG, vec![1]),
+                unknown(linkmodes_attribute::SPEED, 1000u32.to_ne_bytes().to_vec()),
+                unknown(linkmodes_attribute::DUPLEX, vec![1]),
+            ],
+        };
+        assert_eq!(
+            LinkModes::from(&reply),
+            LinkModes {
+                speed: Some(1000),
+                duplex: Some(Duplex::Full),
+                autoneg: Some(true),
+            }
+        );
+
+        let reply = GenericMessage {
+            command: ethtool_message::LINKMODES_GET_REPLY,
+            version: FAMILY_VERSION,
+            attributes: vec![
+                unknown(
+                    linkmodes_attribute::SPEED,
+                    SPEED_UNKNOWN.to_ne_bytes().to_vec(),
+                ),
+                unknown(linkmodes_attribute::DUPLEX, vec![0xff]),
+            ],
+        };
+        assert_eq!(LinkModes::from(&reply), LinkModes::default());
+    }
+
+    #[test]
+    fn loopback_link_modes() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Generic, PortId::auto(), 0).unwrap();
+        match get_link_modes(&socket, 1) {
+            Ok(modes) => assert_eq!(modes.duplex, None),
+            Err(error) if error.raw_os_error() == Some(libc::ENOENT) => {
+                eprintln!("skipping: ethtool family unavailable");
+            }
+            Err(error) => assert_eq!(error.raw_os_error(), Some(libc::EOPNOTSUPP)),
+        }
+    }
+}
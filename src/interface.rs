@@ -0,0 +1,479 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::connection::NetlinkConnection;
+use crate::message::route::{Link, MessageType, Route};
+use crate::message::route_attribute::Mac;
+use crate::message::route_builder::{get_link_request, get_links_request, LinkBuilder};
+use crate::message::{NetlinkPayload, PortId};
+use crate::socket::{netlink_groups, NetlinkProtocol, NetlinkSocket};
+use crate::Result;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::time::{Duration, Instant};
+
+/// Interface summary (`ip link show`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceInfo {
+    pub index: i32,
+    pub name: String,
+    /// `IFF_*` flags.
+    pub flags: u32,
+    pub mtu: Option<u32>,
+    pub address: Option<Mac>,
+}
+
+impl From<&Link> for InterfaceInfo {
+    fn from(link: &Link) -> InterfaceInfo {
+        InterfaceInfo {
+            index: link.message.index,
+            name: link.name().unwrap_or_default().to_string(),
+            flags: link.message.flags,
+            mtu: link.mtu(),
+            address: link.address(),
+        }
+    }
+}
+
+/// List all interfaces of the current network namespace.
+pub fn enumerate_interfaces() -> Result<Vec<InterfaceInfo>> {
+    enumerate_interfaces_filtered(|_| true)
+}
+
+/// List the interfaces for which `predicate` returns `true`.
+///
+/// The predicate runs on the parsed link during the dump, no
+/// [`InterfaceInfo`] is built for the rejected ones.
+pub fn enumerate_interfaces_filtered(
+    predicate: impl Fn(&Link) -> bool,
+) -> Result<Vec<InterfaceInfo>> {
+    let mut interfaces = vec![];
+    NetlinkConnection::new(NetlinkProtocol::Route)?.request(
+        get_links_request().build(),
+        |message| {
+            if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                if predicate(&link) {
+                    interfaces.push(InterfaceInfo::from(&link));
+                }
+            }
+        },
+    )?;
+    Ok(interfaces)
+}
+
+/// Interface names by index, filled by a single link dump to annotate
+/// other objects (e.g. route output interfaces) without a request per
+/// entry.
+#[derive(Debug, Clone, Default)]
+pub struct IndexNameCache {
+    names: HashMap<u32, String>,
+}
+
+impl IndexNameCache {
+    /// Dump the interfaces of the current network namespace.
+    pub fn new() -> Result<IndexNameCache> {
+        let mut cache = IndexNameCache::default();
+        NetlinkConnection::new(NetlinkProtocol::Route)?.request(
+            get_links_request().build(),
+            |message| {
+                if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                    cache.insert(&link);
+                }
+            },
+        )?;
+        Ok(cache)
+    }
+
+    /// Add (or rename) the interface `link`, e.g. from a captured dump or
+    /// a link notification.
+    pub fn insert(&mut self, link: &Link) {
+        if let Some(name) = link.name() {
+            self.names
+                .insert(link.message.index as u32, name.to_string());
+        }
+    }
+
+    /// Name of the interface with index `index`.
+    pub fn lookup(&self, index: u32) -> Option<&str> {
+        self.names.get(&index).map(String::as_str)
+    }
+
+    /// Number of interfaces.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// No interface cached.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Name of the output interface of `route`, requested with `RTM_GETLINK`
+/// through `socket`. Returns `None` when the route has no output
+/// interface or the interface doesn't exist anymore.
+pub fn resolve_oif_name(socket: &NetlinkSocket, route: &Route) -> Result<Option<String>> {
+    let Some(index) = route.oif() else {
+        return Ok(None);
+    };
+
+    let mut name = None;
+    let result = NetlinkConnection::from_transport(socket).request(
+        get_link_request(index as i32).build(),
+        |message| {
+            if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                name = link.name().map(str::to_string);
+            }
+        },
+    );
+    match result {
+        Err(error) if error.raw_os_error() == Some(libc::ENODEV) => Ok(None),
+        Err(error) => Err(error),
+        Ok(()) => Ok(name),
+    }
+}
+
+/// Set the interface administrative state to up (`ip link set dev X up`).
+pub fn set_interface_up(index: i32) -> Result<()> {
+    NetlinkConnection::new(NetlinkProtocol::Route)?
+        .execute(LinkBuilder::set_link(index).up().build())
+}
+
+/// Set the interface administrative state to down
+/// (`ip link set dev X down`).
+pub fn set_interface_down(index: i32) -> Result<()> {
+    NetlinkConnection::new(NetlinkProtocol::Route)?
+        .execute(LinkBuilder::set_link(index).down().build())
+}
+
+/// List the interfaces of group `group` (`ip link show group X`).
+pub fn enumerate_group(group: u32) -> Result<Vec<InterfaceInfo>> {
+    enumerate_interfaces_filtered(|link| link.group().unwrap_or(0) == group)
+}
+
+fn is_up(link: &Link) -> bool {
+    // Interfaces without operational state tracking (e.g. dummy or
+    // loopback) report unknown.
+    link.message.flags & libc::IFF_UP as u32 != 0
+        && matches!(
+            link.operstate().map(i32::from),
+            None | Some(libc::IF_OPER_UP | libc::IF_OPER_UNKNOWN)
+        )
+}
+
+/// Wait until the interface `name` exists and is up (`IFF_UP` and
+/// operationally up), returns its index.
+///
+/// Fails with `TimedOut` when `timeout` elapses first.
+pub fn wait_for_interface_up(name: &str, timeout: Duration) -> Result<i32> {
+    let deadline = Instant::now() + timeout;
+    // Subscribe before the dump so changes in between are not lost.
+    let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), netlink_groups::LINK)?;
+    let interfaces =
+        enumerate_interfaces_filtered(|link| link.name() == Some(name) && is_up(link))?;
+    if let Some(interface) = interfaces.first() {
+        return Ok(interface.index);
+    }
+
+    let mut buffer = vec![0u8; socket.max_recv_size()?];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(
+                Error::new(ErrorKind::TimedOut, format!("interface {} not up", name)).into(),
+            );
+        }
+        socket.set_recv_timeout(Some(remaining))?;
+
+        let messages = match socket.recv_message(&mut buffer) {
+            Ok(messages) => messages,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => continue,
+            Err(error) => return Err(error.into()),
+        };
+        for message in messages {
+            let message = message?;
+            if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                if message.header.kind == libc::RTM_NEWLINK
+                    && link.name() == Some(name)
+                    && is_up(&link)
+                {
+                    return Ok(link.message.index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod interface_test {
+    use super::*;
+    use crate::message::route_builder::RouteBuilder;
+    use std::net::IpAddr;
+
+    /// Run `test` in a new network namespace, returns `false` if the
+    /// namespace could not be created (e.g. missing privileges).
+    fn in_new_netns<F: FnOnce() + Send + 'static>(test: F) -> bool {
+        std::thread::spawn(move || {
+            if unsafe { libc::unshare(libc::CLONE_NEWNET) } == -1 {
+                return false;
+            }
+            test();
+            true
+        })
+        .join()
+        .unwrap()
+    }
+
+    fn interface_flags(index: i32) -> u32 {
+        let mut flags = None;
+        let mut connection = NetlinkConnection::new(NetlinkProtocol::Route).unwrap();
+        connection
+            .request(get_links_request().build(), |message| {
+                if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                    if link.message.index == index {
+                        flags = Some(link.message.flags);
+                    }
+                }
+            })
+            .unwrap();
+        flags.unwrap()
+    }
+
+    #[test]
+    fn interface_up_down() {
+        let created = in_new_netns(|| {
+            // Loopback is always index 1 and starts down in new namespaces.
+            assert_eq!(interface_flags(1) & libc::IFF_UP as u32, 0);
+
+            set_interface_up(1).unwrap();
+            assert_ne!(interface_flags(1) & libc::IFF_UP as u32, 0);
+
+            set_interface_down(1).unwrap();
+            assert_eq!(interface_flags(1) & libc::IFF_UP as u32, 0);
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+
+    #[test]
+    fn interface_missing() {
+        let created = in_new_netns(|| {
+            let error = set_interface_up(1000).unwrap_err();
+            assert_eq!(error.raw_os_error(), Some(libc::ENODEV));
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+
+    #[test]
+    fn enumerate_loopback() {
+        let interfaces = enumerate_interfaces_filtered(|link| {
+            link.message.flags & libc::IFF_LOOPBACK as u32 != 0
+        })
+        .unwrap();
+
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].name, "lo");
+        assert!(enumerate_interfaces().unwrap().len() >= interfaces.len());
+    }
+
+    #[test]
+    fn index_name_cache() {
+        // Captured dump: loopback, eth0 and the end of the dump.
+        let mut dump = vec![];
+        for (index, name) in [(1i32, &b"lo\0\0"[..]), (2, b"eth0\0\0\0\0")] {
+            dump.extend_from_slice(&((16 + 16 + 4 + name.len()) as u32).to_ne_bytes());
+            dump.extend_from_slice(&libc::RTM_NEWLINK.to_ne_bytes());
+            dump.extend_from_slice(&(libc::NLM_F_MULTI as u16).to_ne_bytes());
+            dump.extend_from_slice(&[0; 8]); // Sequence and port ID
+            dump.extend_from_slice(&[0; 4]); // Family, padding and type
+            dump.extend_from_slice(&index.to_ne_bytes());
+            dump.extend_from_slice(&[0; 8]); // Flags and change
+            let length = 4 + name.iter().position(|&c| c == 0).unwrap() + 1;
+            dump.extend_from_slice(&(length as u16).to_ne_bytes());
+            dump.extend_from_slice(&libc::IFLA_IFNAME.to_ne_bytes());
+            dump.extend_from_slice(name);
+        }
+        dump.extend_from_slice(&20u32.to_ne_bytes());
+        dump.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+        dump.extend_from_slice(&(libc::NLM_F_MULTI as u16).to_ne_bytes());
+        dump.extend_from_slice(&[0; 12]);
+
+        let mut cache = IndexNameCache::default();
+        for message in crate::message::NetlinkMessageIterator::new(&dump) {
+            if let NetlinkPayload::Route(MessageType::Link(link)) = message.unwrap().payload {
+                cache.insert(&link);
+            }
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.lookup(1), Some("lo"));
+        assert_eq!(cache.lookup(2), Some("eth0"));
+        assert_eq!(cache.lookup(3), None);
+
+        // Loopback is always the first interface.
+        let cache = IndexNameCache::new().unwrap();
+        assert_eq!(cache.lookup(1), Some("lo"));
+    }
+
+    fn route(builder: RouteBuilder) -> Route {
+        match builder.build().payload {
+            NetlinkPayload::Route(MessageType::Route(route)) => route,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn route_oif_name() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        let destination = IpAddr::from([192, 0, 2, 0]);
+
+        // Loopback is always the first interface.
+        let loopback = route(RouteBuilder::add_route(destination, 24).oif(1));
+        assert_eq!(
+            resolve_oif_name(&socket, &loopback).unwrap(),
+            Some("lo".to_string())
+        );
+
+        let missing = route(RouteBuilder::add_route(destination, 24).oif(i32::MAX as u32));
+        assert_eq!(resolve_oif_name(&socket, &missing).unwrap(), None);
+
+        let nexthop = route(RouteBuilder::add_route(destination, 24).nexthop_id(1));
+        assert_eq!(resolve_oif_name(&socket, &nexthop).unwrap(), None);
+    }
+
+    #[test]
+    fn enumerate_default_group() {
+        // Interfaces start in the default group.
+        assert_eq!(
+            enumerate_group(0).unwrap().len(),
+            enumerate_interfaces().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn enumerate_moved_group() {
+        let created = in_new_netns(|| {
+            NetlinkConnection::new(NetlinkProtocol::Route)
+                .unwrap()
+                .execute(LinkBuilder::set_link(1).group(5).build())
+                .unwrap();
+
+            let interfaces = enumerate_group(5).unwrap();
+            assert_eq!(interfaces.len(), 1);
+            assert_eq!(interfaces[0].index, 1);
+            assert!(enumerate_group(0).unwrap().is_empty());
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+
+    #[test]
+    fn add_link_echo() {
+        let created = in_new_netns(|| {
+            let mut index = None;
+            let mut connection = NetlinkConnection::new(NetlinkProtocol::Route).unwrap();
+            let result = connection.execute_echo(
+                LinkBuilder::add_link("bridge0", "bridge").echo().build(),
+                |message| {
+                    if let NetlinkPayload::Route(MessageType::Link(link)) = message.payload {
+                        index = Some(link.message.index);
+                    }
+                },
+            );
+            if let Err(error) = result {
+                eprintln!("skipping: unable to create bridge interface: {}", error);
+                return;
+            }
+
+            // Loopback is index 1.
+            let index = index.unwrap();
+            assert!(index > 1);
+            assert_eq!(interface_flags(index) & libc::IFF_UP as u32, 0);
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+
+    #[test]
+    fn create_delete_dummy() {
+        let created = in_new_netns(|| {
+            let mut connection = NetlinkConnection::new(NetlinkProtocol::Route).unwrap();
+            if let Err(error) = connection.execute(LinkBuilder::create_dummy("dummy0").build()) {
+                eprintln!("skipping: unable to create dummy interface: {}", error);
+                return;
+            }
+            let names = |interfaces: Vec<InterfaceInfo>| -> Vec<String> {
+                interfaces.into_iter().map(|info| info.name).collect()
+            };
+            assert!(names(enumerate_interfaces().unwrap()).contains(&"dummy0".to_string()));
+
+            connection
+                .execute(LinkBuilder::del_link("dummy0").build())
+                .unwrap();
+            assert_eq!(names(enumerate_interfaces().unwrap()), vec!["lo"]);
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+
+    #[test]
+    fn wait_for_interface() {
+        let created = in_new_netns(|| {
+            let mut connection = NetlinkConnection::new(NetlinkProtocol::Route).unwrap();
+            let name = match connection.execute(LinkBuilder::create_dummy("dummy0").build()) {
+                Ok(()) => "dummy0",
+                Err(error) => {
+                    // Loopback also starts down in new namespaces.
+                    eprintln!("using loopback, unable to create dummy: {}", error);
+                    "lo"
+                }
+            };
+            let index =
+                enumerate_interfaces_filtered(|link| link.name() == Some(name)).unwrap()[0].index;
+
+            let error = wait_for_interface_up(name, Duration::from_millis(50)).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::TimedOut);
+
+            // Threads share the namespace of the thread creating them.
+            let waiter =
+                std::thread::spawn(move || wait_for_interface_up(name, Duration::from_secs(5)));
+            std::thread::sleep(Duration::from_millis(50));
+            set_interface_up(index).unwrap();
+            assert_eq!(waiter.join().unwrap().unwrap(), index);
+
+            // Already up.
+            assert_eq!(
+                wait_for_interface_up(name, Duration::from_millis(50)).unwrap(),
+                index
+            );
+        });
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+}
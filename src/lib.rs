@@ -26,8 +26,69 @@
 //! It uses minimum amount of code and libraries to let the developers
 //! write their applications as they see fit.
 
+/// Netlink protocols enumeration.
+pub mod protocol;
+
+/// Crate error and result types.
+pub mod error;
+
+pub use error::{Error, Result};
+
 /// Netlink socket handling module.
+#[cfg(feature = "socket")]
 pub mod socket;
 
 /// Netlink message handling module.
+///
+/// Parsing and serialization only work on byte buffers and never call the
+/// operating system, the module builds without the `socket` feature and
+/// works with any transport (e.g. captured traffic):
+///
+/// ```
+/// use netlink_rs::message::route::MessageType;
+/// use netlink_rs::message::{NetlinkMessageIterator, NetlinkPayload};
+///
+/// // `RTM_NEWLINK` of the loopback interface (`IFLA_IFNAME` only).
+/// let mut captured = vec![];
+/// captured.extend_from_slice(&40u32.to_ne_bytes());
+/// captured.extend_from_slice(&libc::RTM_NEWLINK.to_ne_bytes());
+/// captured.extend_from_slice(&[0; 10]);
+/// captured.extend_from_slice(&[0, 0]);
+/// captured.extend_from_slice(&libc::ARPHRD_LOOPBACK.to_ne_bytes());
+/// captured.extend_from_slice(&1i32.to_ne_bytes());
+/// captured.extend_from_slice(&[0; 8]);
+/// captured.extend_from_slice(&7u16.to_ne_bytes());
+/// captured.extend_from_slice(&libc::IFLA_IFNAME.to_ne_bytes());
+/// captured.extend_from_slice(b"lo\0\0");
+///
+/// for message in NetlinkMessageIterator::new(&captured) {
+///     match message.unwrap().payload {
+///         NetlinkPayload::Route(MessageType::Link(link)) => {
+///             assert_eq!(link.message.index, 1);
+///             assert_eq!(link.name(), Some("lo"));
+///         }
+///         _ => unreachable!(),
+///     }
+/// }
+/// ```
 pub mod message;
+
+/// Netlink request/reply handling module.
+#[cfg(feature = "socket")]
+pub mod connection;
+
+/// Network interface management helpers.
+#[cfg(feature = "socket")]
+pub mod interface;
+
+/// Routing table management helpers.
+#[cfg(feature = "socket")]
+pub mod routing;
+
+/// Network namespace helpers.
+#[cfg(feature = "socket")]
+pub mod namespace;
+
+/// Ethtool (generic netlink) helpers.
+#[cfg(feature = "socket")]
+pub mod ethtool;
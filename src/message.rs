@@ -21,18 +21,35 @@
 // OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
 // SUCH DAMAGE.
 
+pub mod generic;
 pub mod packet_parser;
 pub mod packet_writer;
 pub mod route;
 pub mod route_attribute;
+pub mod route_builder;
+pub mod route_event;
+pub mod sock_diag;
+pub mod stream_decoder;
 
+use crate::protocol::NetlinkProtocol;
 use packet_parser::PacketParser;
 use packet_writer::PacketWriter;
 use route::MessageType;
+use route_attribute::Attribute;
+use std::fmt;
 use std::mem;
+use std::net::IpAddr;
+
+/// Netlink error or acknowledgment message type.
+pub const NLMSG_ERROR: u16 = libc::NLMSG_ERROR as u16;
+/// Netlink end of multipart message type.
+pub const NLMSG_DONE: u16 = libc::NLMSG_DONE as u16;
 
 /// Netlink maximum message size
 /// ([source](https://github.com/torvalds/linux/blob/v6.11/include/linux/netlink.h#L273)).
+///
+/// Conservative value: dumps may be larger, see
+/// [`crate::socket::NetlinkSocket::max_recv_size`].
 pub const NETLINK_MESSAGE_MAXIMUM_SIZE: usize = 8192;
 
 /// All possible netlink parse errors.
@@ -42,50 +59,513 @@ pub enum NetlinkParseError {
     MessageTooSmall,
     /// Buffer is truncated (smaller than the header length).
     MessageIncomplete,
+    /// Attribute declared length is too small for its type.
+    InvalidAttributeLength,
+    /// Nested attributes deeper than the
+    /// [`route_attribute::parse_attribute_tree`] maximum depth.
+    NestingTooDeep,
+    /// Attribute without a typed representation in a message checked with
+    /// [`ParseOptions::strict_message_type`].
+    UnknownAttribute { kind: u16 },
+}
+
+impl fmt::Display for NetlinkParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetlinkParseError::MessageTooSmall => {
+                formatter.write_str("netlink message smaller than header")
+            }
+            NetlinkParseError::MessageIncomplete => {
+                formatter.write_str("netlink message truncated")
+            }
+            NetlinkParseError::InvalidAttributeLength => {
+                formatter.write_str("netlink attribute length invalid for its type")
+            }
+            NetlinkParseError::NestingTooDeep => {
+                formatter.write_str("netlink attributes nested too deep")
+            }
+            NetlinkParseError::UnknownAttribute { kind } => {
+                write!(formatter, "netlink attribute type {} not supported", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetlinkParseError {}
+
+/// All possible message building errors.
+#[derive(Debug)]
+pub enum BuildError {
+    /// MTU outside the interface supported range.
+    MtuOutOfRange {
+        mtu: u32,
+        minimum: u32,
+        maximum: u32,
+    },
+    /// Header length doesn't match the serialized message size.
+    LengthMismatch { length: u32, serialized: usize },
+    /// Attribute doesn't fit the 16 bit attribute length.
+    AttributeTooLarge { kind: u16, length: usize },
+    /// Message is larger than [`NETLINK_MESSAGE_MAXIMUM_SIZE`].
+    MessageTooLarge { length: usize },
+    /// Request flag (`NLM_F_REQUEST`) is missing, the kernel ignores the
+    /// message.
+    MissingRequestFlag,
+    /// Route uses a nexthop object (`RTA_NH_ID`) and also has its own
+    /// gateway, output interface or multipath nexthops.
+    ConflictingNextHop,
+    /// Interface name longer than `IFNAMSIZ - 1` (15) bytes.
+    NameTooLong { name: String },
+    /// Interface name with a NUL byte, the kernel would truncate it.
+    NameContainsNul { name: String },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::MtuOutOfRange {
+                mtu,
+                minimum,
+                maximum,
+            } => write!(
+                formatter,
+                "MTU {} outside of interface range {}-{}",
+                mtu, minimum, maximum
+            ),
+            BuildError::LengthMismatch { length, serialized } => write!(
+                formatter,
+                "header length {} differs from message size {}",
+                length, serialized
+            ),
+            BuildError::AttributeTooLarge { kind, length } => {
+                write!(formatter, "attribute {} length {} too large", kind, length)
+            }
+            BuildError::MessageTooLarge { length } => write!(
+                formatter,
+                "message length {} larger than maximum {}",
+                length, NETLINK_MESSAGE_MAXIMUM_SIZE
+            ),
+            BuildError::MissingRequestFlag => formatter.write_str("request flag missing"),
+            BuildError::ConflictingNextHop => {
+                formatter.write_str("route nexthop object used with inline nexthop")
+            }
+            BuildError::NameTooLong { name } => write!(
+                formatter,
+                "interface name {:?} longer than {} bytes",
+                name,
+                libc::IFNAMSIZ - 1
+            ),
+            BuildError::NameContainsNul { name } => {
+                write!(formatter, "interface name {:?} contains NUL byte", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Message parsing behavior options.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Collect attributes without a typed representation in the message
+    /// `unknown` list instead of mixing them as `Attribute::Unknown` with
+    /// the typed attributes.
+    pub separate_unknown: bool,
+    /// Fail messages of this type (e.g. `libc::RTM_NEWLINK`) carrying
+    /// attributes without a typed representation with
+    /// [`NetlinkParseError::UnknownAttribute`] instead of keeping them as
+    /// `Attribute::Unknown`. Only the message attributes are checked, not
+    /// the nested ones.
+    pub strict_message_type: Option<u16>,
 }
 
 /// Netlink header rust version.
 #[repr(C)]
 pub struct NetlinkHeader {
     /// Netlink message length (including this header).
-    length: u32,
+    pub length: u32,
     /// Netlink message type.
-    kind: u16,
+    pub kind: u16,
     /// Netlink flags.
-    flags: u16,
+    pub flags: u16,
     /// Netlink message sequence (for matching request/reply).
-    sequence: u32,
+    pub sequence: u32,
     /// Netlink port identification (to identify the messenger).
-    port_id: u32,
+    pub port_id: PortId,
+}
+
+/// Netlink port ID (`nlmsg_pid` and `nl_pid`), identifies a socket and not
+/// a process: kernel assigned port IDs (see [`PortId::auto`]) are unique
+/// per socket and only the first one usually matches the process PID.
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortId(pub u32);
+
+impl PortId {
+    /// Port ID of the kernel (messages sent by or to the kernel).
+    pub const fn kernel() -> PortId {
+        PortId(0)
+    }
+
+    /// Port ID to bind with to let the kernel assign a unique one.
+    pub const fn auto() -> PortId {
+        PortId(0)
+    }
+
+    /// Whether this is the kernel port ID.
+    pub const fn is_kernel(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<u32> for PortId {
+    fn from(port_id: u32) -> PortId {
+        PortId(port_id)
+    }
+}
+
+impl From<PortId> for u32 {
+    fn from(port_id: PortId) -> u32 {
+        port_id.0
+    }
+}
+
+impl fmt::Display for PortId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+// The parsers and writers assume the `struct nlmsghdr` layout.
+const _: () = assert!(mem::size_of::<NetlinkHeader>() == mem::size_of::<libc::nlmsghdr>());
+const _: () =
+    assert!(mem::offset_of!(NetlinkHeader, kind) == mem::offset_of!(libc::nlmsghdr, nlmsg_type));
+const _: () =
+    assert!(mem::offset_of!(NetlinkHeader, flags) == mem::offset_of!(libc::nlmsghdr, nlmsg_flags));
+const _: () =
+    assert!(mem::offset_of!(NetlinkHeader, sequence) == mem::offset_of!(libc::nlmsghdr, nlmsg_seq));
+const _: () =
+    assert!(mem::offset_of!(NetlinkHeader, port_id) == mem::offset_of!(libc::nlmsghdr, nlmsg_pid));
+
+/// Netlink error message (`NLMSG_ERROR`).
+///
+/// It is also used to acknowledge requests, in that case `error` is zero.
+pub struct ErrorMessage {
+    /// Negative `errno` value or zero for acknowledgments.
+    pub error: i32,
+    /// Header of the message that caused this error.
+    pub header: NetlinkHeader,
 }
 
 /// Netlink possible payload types.
 pub enum NetlinkPayload<'a> {
     None,
+    Error(ErrorMessage),
     Route(route::MessageType),
+    Generic(generic::GenericMessage),
+    SockDiag(sock_diag::MessageType),
     Unknown(&'a [u8]),
 }
 
+impl NetlinkPayload<'_> {
+    /// Type of the first attribute without a typed representation.
+    fn unknown_attribute(&self) -> Option<u16> {
+        let (attributes, unknown): (&[Attribute], &[(u16, Vec<u8>)]) = match self {
+            NetlinkPayload::Route(MessageType::Link(link)) => (&link.attributes, &link.unknown),
+            NetlinkPayload::Route(MessageType::Address(address)) => {
+                (&address.attributes, &address.unknown)
+            }
+            NetlinkPayload::Route(MessageType::Route(route)) => (&route.attributes, &route.unknown),
+            NetlinkPayload::Route(MessageType::NextHop(nexthop)) => {
+                (&nexthop.attributes, &nexthop.unknown)
+            }
+            NetlinkPayload::Route(MessageType::NsId(nsid)) => (&nsid.attributes, &nsid.unknown),
+            NetlinkPayload::Route(MessageType::Stats(stats)) => (&stats.attributes, &stats.unknown),
+            NetlinkPayload::Route(MessageType::Neighbor(neighbor)) => {
+                (&neighbor.attributes, &neighbor.unknown)
+            }
+            NetlinkPayload::Generic(generic) => (&generic.attributes, &[]),
+            NetlinkPayload::SockDiag(sock_diag::MessageType::Message(message)) => {
+                (&message.attributes, &[])
+            }
+            _ => (&[], &[]),
+        };
+
+        unknown.first().map(|(kind, _)| *kind).or_else(|| {
+            attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Unknown(attribute) => Some(attribute.kind),
+                _ => None,
+            })
+        })
+    }
+
+    /// Payload bytes of messages without a parser (see
+    /// [`NetlinkPayload::Unknown`]), `None` for typed payloads.
+    pub fn as_unknown(&self) -> Option<&[u8]> {
+        match self {
+            NetlinkPayload::Unknown(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Serialized payload size in bytes, what [`NetlinkMessage::to_array`]
+    /// writes after the netlink header.
+    ///
+    /// Parsed messages may carry more bytes than kept in typed form (e.g.
+    /// [`ParseOptions::separate_unknown`] attributes or the request echoed
+    /// in errors), see [`NetlinkMessage::payload_len`].
+    pub fn len(&self) -> usize {
+        let (family_header, attributes): (usize, &[Attribute]) = match self {
+            NetlinkPayload::None => (0, &[]),
+            NetlinkPayload::Error(_) => {
+                (mem::size_of::<i32>() + mem::size_of::<NetlinkHeader>(), &[])
+            }
+            NetlinkPayload::Route(MessageType::Link(link)) => {
+                (mem::size_of::<route::LinkMessage>(), &link.attributes)
+            }
+            NetlinkPayload::Route(MessageType::Address(address)) => {
+                (mem::size_of::<route::AddressMessage>(), &address.attributes)
+            }
+            NetlinkPayload::Route(MessageType::Route(route)) => {
+                (mem::size_of::<route::RouteMessage>(), &route.attributes)
+            }
+            NetlinkPayload::Route(MessageType::NextHop(nexthop)) => {
+                (mem::size_of::<route::NextHopMessage>(), &nexthop.attributes)
+            }
+            NetlinkPayload::Route(MessageType::NsId(nsid)) => (
+                mem::size_of::<route::NsIdMessage>().next_multiple_of(4),
+                &nsid.attributes,
+            ),
+            NetlinkPayload::Route(MessageType::Stats(stats)) => {
+                (mem::size_of::<route::StatsMessage>(), &stats.attributes)
+            }
+            NetlinkPayload::Route(MessageType::Neighbor(neighbor)) => (
+                mem::size_of::<route::NeighborMessage>(),
+                &neighbor.attributes,
+            ),
+            NetlinkPayload::Generic(generic) => (generic::GENERIC_HEADER_SIZE, &generic.attributes),
+            NetlinkPayload::SockDiag(sock_diag::MessageType::Request(_)) => {
+                (sock_diag::InetDiagRequest::SIZE, &[])
+            }
+            NetlinkPayload::SockDiag(sock_diag::MessageType::Message(message)) => {
+                (sock_diag::InetDiagMessage::SIZE, &message.attributes)
+            }
+            NetlinkPayload::Unknown(bytes) => (bytes.len(), &[]),
+        };
+
+        family_header + attributes.iter().map(Attribute::length).sum::<usize>()
+    }
+
+    /// Payload without any bytes (e.g. `NLMSG_DONE` without error code).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Netlink message category, see [`NetlinkMessage::category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageCategory {
+    /// Request failure reported by the kernel (`NLMSG_ERROR` with non zero
+    /// error code).
+    Error,
+    /// End of a multipart reply (`NLMSG_DONE`).
+    Done,
+    /// Unsolicited message (e.g. multicast group event).
+    Notification,
+    /// Reply to a request, including acknowledgments.
+    Reply,
+}
+
 /// Netlink rust representation.
 pub struct NetlinkMessage<'a> {
     /// Netlink header.
     pub header: NetlinkHeader,
     /// Netlink payload.
     pub payload: NetlinkPayload<'a>,
+    /// Bytes this message was parsed from.
+    raw: &'a [u8],
 }
 
 type NetlinkParseResult<T> = Result<T, NetlinkParseError>;
 
-impl NetlinkMessage<'_> {
+impl ErrorMessage {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<ErrorMessage> {
+        if (parser.remaining() as usize) < 4 + mem::size_of::<NetlinkHeader>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        Ok(ErrorMessage {
+            error: parser.read_i32(),
+            header: NetlinkHeader {
+                length: parser.read_u32(),
+                kind: parser.read_u16(),
+                flags: parser.read_u16(),
+                sequence: parser.read_u32(),
+                port_id: PortId(parser.read_u32()),
+            },
+        })
+    }
+}
+
+impl<'a> NetlinkMessage<'a> {
     /// Read bytes from `AF_NETLINK` or custom interfaces and turn into netlink
     /// data structures.
-    pub fn from(bytes: &[u8]) -> NetlinkParseResult<NetlinkMessage> {
+    ///
+    /// Payloads are decoded as routing (`NETLINK_ROUTE`) messages, use
+    /// [`NetlinkMessage::from_protocol`] for other protocols.
+    pub fn from(bytes: &'a [u8]) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::from_with_options(bytes, ParseOptions::default())
+    }
+
+    /// Same as [`NetlinkMessage::from`], but also returns the bytes used by
+    /// the message including the alignment padding (`NLMSG_ALIGN` of the
+    /// header length): the offset of the next message in `bytes`.
+    pub fn from_with_len(bytes: &'a [u8]) -> NetlinkParseResult<(NetlinkMessage<'a>, usize)> {
+        let message = NetlinkMessage::from(bytes)?;
+        let length = message.aligned_length();
+        Ok((message, length))
+    }
+
+    fn aligned_length(&self) -> usize {
+        (self.header.length as usize).next_multiple_of(4)
+    }
+
+    /// Same as [`NetlinkMessage::from`], but using custom parsing `options`.
+    pub fn from_with_options(
+        bytes: &'a [u8],
+        options: ParseOptions,
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        let mut parser = PacketParser::new(bytes);
+        parser.set_options(options);
+        NetlinkMessage::parse_route(&mut parser, bytes)
+    }
+
+    fn parse_route(
+        parser: &mut PacketParser<'a>,
+        bytes: &'a [u8],
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse(parser, bytes, |kind, parser| match kind {
+            libc::RTM_GETLINK
+            | libc::RTM_NEWLINK
+            | libc::RTM_DELLINK
+            | libc::RTM_SETLINK
+            | route::message_type::NEWLINKPROP
+            | route::message_type::DELLINKPROP => Some(NetlinkPayload::Route(MessageType::Link(
+                route::Link::from(parser).ok()?,
+            ))),
+            libc::RTM_GETADDR | libc::RTM_NEWADDR | libc::RTM_DELADDR => Some(
+                NetlinkPayload::Route(MessageType::Address(route::Address::from(parser).ok()?)),
+            ),
+            libc::RTM_GETROUTE | libc::RTM_NEWROUTE | libc::RTM_DELROUTE => Some(
+                NetlinkPayload::Route(MessageType::Route(route::Route::from(parser).ok()?)),
+            ),
+            route::message_type::NEWNEXTHOP
+            | route::message_type::DELNEXTHOP
+            | route::message_type::GETNEXTHOP => Some(NetlinkPayload::Route(MessageType::NextHop(
+                route::NextHopObject::from(parser).ok()?,
+            ))),
+            libc::RTM_NEWNSID | libc::RTM_DELNSID | libc::RTM_GETNSID => Some(
+                NetlinkPayload::Route(MessageType::NsId(route::NsId::from(parser).ok()?)),
+            ),
+            libc::RTM_NEWNEIGH | libc::RTM_DELNEIGH | libc::RTM_GETNEIGH => Some(
+                NetlinkPayload::Route(MessageType::Neighbor(route::Neighbor::from(parser).ok()?)),
+            ),
+            route::message_type::NEWSTATS | route::message_type::GETSTATS => {
+                Some(NetlinkPayload::Route(MessageType::Stats(
+                    route::LinkStatsExtended::from(parser).ok()?,
+                )))
+            }
+            _ => None,
+        })
+    }
+
+    /// Read bytes from a socket bound to `protocol` and turn into netlink
+    /// data structures.
+    ///
+    /// Message types are protocol specific (e.g. `RTM_NEWLINK` and
+    /// `GENL_ID_CTRL` share the same value), so payloads of protocols
+    /// without a decoder are returned as [`NetlinkPayload::Unknown`].
+    pub fn from_protocol(
+        bytes: &'a [u8],
+        protocol: NetlinkProtocol,
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse_protocol(&mut PacketParser::new(bytes), bytes, protocol)
+    }
+
+    fn parse_protocol(
+        parser: &mut PacketParser<'a>,
+        bytes: &'a [u8],
+        protocol: NetlinkProtocol,
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        match protocol {
+            NetlinkProtocol::Route => NetlinkMessage::parse_route(parser, bytes),
+            NetlinkProtocol::Generic => NetlinkMessage::parse_generic(parser, bytes),
+            NetlinkProtocol::SockDiag => NetlinkMessage::parse_sock_diag(parser, bytes),
+            _ => NetlinkMessage::parse(parser, bytes, |_, _| None),
+        }
+    }
+
+    /// Read bytes from a `NETLINK_GENERIC` socket and turn into netlink data
+    /// structures.
+    pub fn from_generic(bytes: &'a [u8]) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse_generic(&mut PacketParser::new(bytes), bytes)
+    }
+
+    fn parse_generic(
+        parser: &mut PacketParser<'a>,
+        bytes: &'a [u8],
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse(parser, bytes, |kind, parser| {
+            if kind < libc::GENL_MIN_ID as u16 {
+                return None;
+            }
+
+            Some(NetlinkPayload::Generic(
+                generic::GenericMessage::from(parser).ok()?,
+            ))
+        })
+    }
+
+    /// Read bytes from a `NETLINK_SOCK_DIAG` socket and turn into netlink
+    /// data structures.
+    pub fn from_sock_diag(bytes: &'a [u8]) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse_sock_diag(&mut PacketParser::new(bytes), bytes)
+    }
+
+    fn parse_sock_diag(
+        parser: &mut PacketParser<'a>,
+        bytes: &'a [u8],
+    ) -> NetlinkParseResult<NetlinkMessage<'a>> {
+        NetlinkMessage::parse(parser, bytes, |kind, parser| {
+            if kind != sock_diag::SOCK_DIAG_BY_FAMILY {
+                return None;
+            }
+
+            Some(NetlinkPayload::SockDiag(sock_diag::MessageType::Message(
+                sock_diag::InetDiagMessage::from(parser).ok()?,
+            )))
+        })
+    }
+
+    /// Parse the netlink header and the control messages, then use
+    /// `parse_payload` for the protocol specific messages. `parser` is
+    /// reset to `bytes`, keeping its options, so callers can reuse it.
+    ///
+    /// Payloads that fail to parse are returned as
+    /// [`NetlinkPayload::Unknown`].
+    fn parse<F>(
+        parser: &mut PacketParser<'a>,
+        bytes: &'a [u8],
+        parse_payload: F,
+    ) -> NetlinkParseResult<NetlinkMessage<'a>>
+    where
+        F: FnOnce(u16, &mut PacketParser<'a>) -> Option<NetlinkPayload<'a>>,
+    {
         if bytes.len() < mem::size_of::<NetlinkHeader>() {
             return Err(NetlinkParseError::MessageIncomplete);
         }
 
-        let mut parser = PacketParser::new(bytes);
-        let length = parser.read_u32();
+        let length = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
         if (length as usize) > bytes.len() {
             return Err(NetlinkParseError::MessageIncomplete);
         }
@@ -93,11 +573,15 @@ impl NetlinkMessage<'_> {
             return Err(NetlinkParseError::MessageTooSmall);
         }
 
+        let bytes = &bytes[..length as usize];
+        parser.reset(bytes);
+        parser.skip(4);
+
         parser.set_netlink_length(length);
         let kind = parser.read_u16();
         let flags = parser.read_u16();
         let sequence = parser.read_u32();
-        let port_id = parser.read_u32();
+        let port_id = PortId(parser.read_u32());
         let netlink_header = NetlinkHeader {
             length,
             kind,
@@ -106,24 +590,214 @@ impl NetlinkMessage<'_> {
             port_id,
         };
 
-        match kind {
-            libc::RTM_GETLINK | libc::RTM_NEWLINK | libc::RTM_DELLINK | libc::RTM_SETLINK => {
-                match route::Link::from(&mut parser) {
-                    Ok(link) => Ok(NetlinkMessage {
-                        header: netlink_header,
-                        payload: NetlinkPayload::Route(MessageType::Link(link)),
-                    }),
-                    Err(_) => Ok(NetlinkMessage {
-                        header: netlink_header,
-                        payload: NetlinkPayload::Unknown(&bytes[16..]),
-                    }),
+        let payload = match kind {
+            NLMSG_ERROR => ErrorMessage::from(parser).ok().map(NetlinkPayload::Error),
+            _ => parse_payload(kind, parser),
+        };
+
+        let payload = payload.unwrap_or(NetlinkPayload::Unknown(&bytes[16..]));
+        if parser.options().strict_message_type == Some(kind) {
+            if let Some(kind) = payload.unknown_attribute() {
+                return Err(NetlinkParseError::UnknownAttribute { kind });
+            }
+        }
+
+        Ok(NetlinkMessage {
+            header: netlink_header,
+            payload,
+            raw: bytes,
+        })
+    }
+
+    /// Create a message to be serialized with [`NetlinkMessage::to_array`].
+    pub fn new(header: NetlinkHeader, payload: NetlinkPayload<'a>) -> NetlinkMessage<'a> {
+        NetlinkMessage {
+            header,
+            payload,
+            raw: &[],
+        }
+    }
+
+    /// Request the route the kernel would use to reach `destination`
+    /// (`RTM_GETROUTE` without `NLM_F_DUMP`): the reply is a single route
+    /// message with the selected nexthop.
+    pub fn get_route_to(destination: IpAddr, sequence: u32) -> NetlinkMessage<'static> {
+        let mut message = route_builder::RouteBuilder::get_route(destination).build();
+        message.header.sequence = sequence;
+        message
+    }
+
+    /// Classify the message: errors and `NLMSG_DONE` by type, then
+    /// messages with sequence zero as notifications (requests sent through
+    /// [`crate::connection::NetlinkConnection`] never use sequence zero).
+    ///
+    /// Notifications caused by requests of other sockets carry the
+    /// sequence and port ID of that request and are classified as
+    /// [`MessageCategory::Reply`], use [`NetlinkMessage::category_for`] on
+    /// sockets receiving both.
+    pub fn category(&self) -> MessageCategory {
+        match (&self.payload, self.header.kind) {
+            (NetlinkPayload::Error(error), _) if error.error != 0 => MessageCategory::Error,
+            (_, NLMSG_ERROR) => MessageCategory::Reply,
+            (_, NLMSG_DONE) => MessageCategory::Done,
+            _ if self.header.sequence == 0 => MessageCategory::Notification,
+            _ => MessageCategory::Reply,
+        }
+    }
+
+    /// Same as [`NetlinkMessage::category`] for a message received by the
+    /// socket with port ID `port_id` (see
+    /// [`crate::socket::NetlinkSocket::port_id`]): messages addressed to
+    /// other ports are notifications.
+    pub fn category_for(&self, port_id: PortId) -> MessageCategory {
+        match self.category() {
+            MessageCategory::Reply if self.header.port_id != port_id => {
+                MessageCategory::Notification
+            }
+            category => category,
+        }
+    }
+
+    /// Original on-wire bytes (exactly `header.length` bytes) of a parsed
+    /// message.
+    ///
+    /// Messages not created by parsing return an empty slice.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Payload size in bytes (the message length without the netlink
+    /// header): as received for parsed messages, otherwise
+    /// [`NetlinkPayload::len`].
+    pub fn payload_len(&self) -> usize {
+        if self.raw.is_empty() {
+            return self.payload.len();
+        }
+
+        self.raw.len() - mem::size_of::<NetlinkHeader>()
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        match &self.payload {
+            NetlinkPayload::Route(MessageType::Link(link)) => &link.attributes,
+            NetlinkPayload::Route(MessageType::Address(address)) => &address.attributes,
+            NetlinkPayload::Route(MessageType::Route(route)) => &route.attributes,
+            NetlinkPayload::Route(MessageType::NextHop(nexthop)) => &nexthop.attributes,
+            NetlinkPayload::Route(MessageType::NsId(nsid)) => &nsid.attributes,
+            NetlinkPayload::Route(MessageType::Stats(stats)) => &stats.attributes,
+            NetlinkPayload::Route(MessageType::Neighbor(neighbor)) => &neighbor.attributes,
+            NetlinkPayload::Generic(generic) => &generic.attributes,
+            _ => &[],
+        }
+    }
+
+    /// Size of the message written by [`NetlinkMessage::to_array`].
+    fn serialized_length(&self) -> usize {
+        let family_header = match &self.payload {
+            NetlinkPayload::Error(_) => mem::size_of::<i32>() + mem::size_of::<NetlinkHeader>(),
+            NetlinkPayload::Route(MessageType::Link(_)) => mem::size_of::<route::LinkMessage>(),
+            NetlinkPayload::Route(MessageType::Address(_)) => {
+                mem::size_of::<route::AddressMessage>()
+            }
+            NetlinkPayload::Route(MessageType::Route(_)) => mem::size_of::<route::RouteMessage>(),
+            NetlinkPayload::Route(MessageType::NextHop(_)) => {
+                mem::size_of::<route::NextHopMessage>()
+            }
+            NetlinkPayload::Route(MessageType::NsId(_)) => {
+                mem::size_of::<route::NsIdMessage>().next_multiple_of(4)
+            }
+            NetlinkPayload::Route(MessageType::Stats(_)) => mem::size_of::<route::StatsMessage>(),
+            NetlinkPayload::Route(MessageType::Neighbor(_)) => {
+                mem::size_of::<route::NeighborMessage>()
+            }
+            NetlinkPayload::Generic(_) => generic::GENERIC_HEADER_SIZE,
+            NetlinkPayload::SockDiag(sock_diag::MessageType::Request(_)) => {
+                sock_diag::InetDiagRequest::SIZE
+            }
+            _ => 0,
+        };
+
+        mem::size_of::<NetlinkHeader>()
+            + family_header
+            + self
+                .attributes()
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>()
+    }
+
+    /// Check the message before sending: the header length must match the
+    /// serialized size, every attribute must fit its length field and the
+    /// request flag must be set.
+    ///
+    /// Catches mistakes that the kernel would otherwise report as a plain
+    /// `EINVAL` (or silently ignore).
+    pub fn validate(&self) -> Result<(), BuildError> {
+        if (self.header.flags & libc::NLM_F_REQUEST as u16) == 0 {
+            return Err(BuildError::MissingRequestFlag);
+        }
+
+        if let NetlinkPayload::Route(MessageType::Route(route)) = &self.payload {
+            if route.nexthop_id().is_some() && route.has_inline_nexthop() {
+                return Err(BuildError::ConflictingNextHop);
+            }
+        }
+
+        if let NetlinkPayload::Route(MessageType::Link(link)) = &self.payload {
+            for name in link.name().into_iter().chain(link.veth_peer_name()) {
+                if name.len() > libc::IFNAMSIZ - 1 {
+                    return Err(BuildError::NameTooLong {
+                        name: name.to_string(),
+                    });
+                }
+                if name.contains('\0') {
+                    return Err(BuildError::NameContainsNul {
+                        name: name.to_string(),
+                    });
                 }
             }
-            _ => Ok(NetlinkMessage {
-                header: netlink_header,
-                payload: NetlinkPayload::Unknown(&bytes[16..]),
-            }),
         }
+
+        if let Some(attribute) = self
+            .attributes()
+            .iter()
+            .find(|attribute| attribute.length() > u16::MAX as usize)
+        {
+            return Err(BuildError::AttributeTooLarge {
+                kind: attribute.kind(),
+                length: attribute.length(),
+            });
+        }
+
+        let serialized = self.serialized_length();
+        if serialized > NETLINK_MESSAGE_MAXIMUM_SIZE {
+            return Err(BuildError::MessageTooLarge { length: serialized });
+        }
+        if self.header.length as usize != serialized {
+            return Err(BuildError::LengthMismatch {
+                length: self.header.length,
+                serialized,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Overwrite the header of the message in `bytes` (e.g. to update the
+    /// sequence or flags of a raw message before forwarding it), the
+    /// payload is left untouched.
+    pub fn rewrite_header(bytes: &mut [u8], header: &NetlinkHeader) -> NetlinkParseResult<()> {
+        if bytes.len() < mem::size_of::<NetlinkHeader>() {
+            return Err(NetlinkParseError::MessageTooSmall);
+        }
+
+        let mut writer = PacketWriter::new(bytes);
+        writer.write_u32(header.length);
+        writer.write_u16(header.kind);
+        writer.write_u16(header.flags);
+        writer.write_u32(header.sequence);
+        writer.write_u32(header.port_id.0);
+        Ok(())
     }
 
     /// Transform netlink data structures into binaries for interfaces.
@@ -134,11 +808,102 @@ impl NetlinkMessage<'_> {
         writer.write_u16(self.header.kind);
         writer.write_u16(self.header.flags);
         writer.write_u32(self.header.sequence);
-        writer.write_u32(self.header.port_id);
+        writer.write_u32(self.header.port_id.0);
+        match self.payload {
+            NetlinkPayload::Error(error) => {
+                writer.write_i32(error.error);
+                writer.write_u32(error.header.length);
+                writer.write_u16(error.header.kind);
+                writer.write_u16(error.header.flags);
+                writer.write_u32(error.header.sequence);
+                writer.write_u32(error.header.port_id.0);
+            }
+            NetlinkPayload::Route(MessageType::Link(link)) => link.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::Address(address)) => address.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::Route(route)) => route.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::NextHop(nexthop)) => nexthop.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::NsId(nsid)) => nsid.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::Stats(stats)) => stats.to_array(&mut writer),
+            NetlinkPayload::Route(MessageType::Neighbor(neighbor)) => {
+                neighbor.to_array(&mut writer)
+            }
+            NetlinkPayload::Generic(generic) => generic.to_array(&mut writer),
+            NetlinkPayload::SockDiag(sock_diag::MessageType::Request(request)) => {
+                request.to_array(&mut writer)
+            }
+            _ => (),
+        }
         writer.written_total()
     }
 }
 
+/// Iterate over all netlink messages contained in a buffer (e.g. a single
+/// `recv()` call).
+///
+/// The iteration ends when less than a header is left or at a zero length
+/// header (padding), but a message longer than the remaining bytes is
+/// reported as [`NetlinkParseError::MessageIncomplete`].
+pub struct NetlinkMessageIterator<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    protocol: NetlinkProtocol,
+    /// Reset for every message instead of creating one per message.
+    parser: PacketParser<'a>,
+}
+
+impl<'a> NetlinkMessageIterator<'a> {
+    /// Iterate over `NETLINK_ROUTE` messages.
+    pub fn new(bytes: &'a [u8]) -> NetlinkMessageIterator<'a> {
+        NetlinkMessageIterator::with_protocol(bytes, NetlinkProtocol::Route)
+    }
+
+    /// Iterate over messages parsed with [`NetlinkMessage::from_protocol`].
+    pub fn with_protocol(bytes: &'a [u8], protocol: NetlinkProtocol) -> NetlinkMessageIterator<'a> {
+        NetlinkMessageIterator {
+            bytes,
+            offset: 0,
+            protocol,
+            parser: PacketParser::new(bytes),
+        }
+    }
+}
+
+impl<'a> Iterator for NetlinkMessageIterator<'a> {
+    type Item = NetlinkParseResult<NetlinkMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Not enough bytes left for a header: end of the buffer (e.g. short
+        // read or padding).
+        if self.bytes.len().saturating_sub(self.offset) < mem::size_of::<NetlinkHeader>() {
+            self.offset = self.bytes.len();
+            return None;
+        }
+
+        // Zero length header: trailing zero padding, not a message.
+        let length = &self.bytes[self.offset..self.offset + 4];
+        if length == [0; 4] {
+            self.offset = self.bytes.len();
+            return None;
+        }
+
+        match NetlinkMessage::parse_protocol(
+            &mut self.parser,
+            &self.bytes[self.offset..],
+            self.protocol,
+        ) {
+            Ok(message) => {
+                self.offset += message.aligned_length();
+                Some(Ok(message))
+            }
+            Err(error) => {
+                // Stop the iteration: we can't find the next message.
+                self.offset = self.bytes.len();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod message_test {
     use crate::message::*;
@@ -152,68 +917,81 @@ mod message_test {
             0x00, 0x00, 0x00, 0x00, // Sequence
             0x00, 0x00, 0x00, // Port ID (missing 1 byte)
         ];
-        match NetlinkMessage::from(&message) {
-            Err(NetlinkParseError::MessageIncomplete) => assert!(true),
-            _ => assert!(false),
-        }
+        assert!(matches!(
+            NetlinkMessage::from(&message),
+            Err(NetlinkParseError::MessageIncomplete)
+        ));
+    }
+
+    #[test]
+    fn port_id() {
+        assert_eq!(PortId::kernel(), PortId(0));
+        assert_eq!(PortId::auto(), PortId::kernel());
+        assert!(PortId::kernel().is_kernel());
+        assert!(!PortId(4242).is_kernel());
+        assert_ne!(PortId(4242), PortId(4243));
+        assert!(PortId(1) < PortId(2));
+        assert_eq!(PortId::from(4242), PortId(4242));
+        assert_eq!(u32::from(PortId(4242)), 4242);
+        assert_eq!(PortId(4242).to_string(), "4242");
     }
 
     #[test]
     fn wrong_message_length() {
-        let message = NetlinkMessage {
-            header: NetlinkHeader {
+        let message = NetlinkMessage::new(
+            NetlinkHeader {
                 length: 15,
                 kind: 0,
                 flags: 0,
                 sequence: 0,
-                port_id: 0,
+                port_id: PortId::kernel(),
             },
-            payload: NetlinkPayload::None,
-        };
+            NetlinkPayload::None,
+        );
         let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
         message.to_array(&mut bytes);
 
-        match NetlinkMessage::from(&bytes) {
-            Err(NetlinkParseError::MessageTooSmall) => assert!(true),
-            _ => assert!(false),
-        }
+        assert!(matches!(
+            NetlinkMessage::from(&bytes),
+            Err(NetlinkParseError::MessageTooSmall)
+        ));
     }
 
     #[test]
     fn message_incomplete() {
-        let message = NetlinkMessage {
-            header: NetlinkHeader {
+        let message = NetlinkMessage::new(
+            NetlinkHeader {
                 length: 17,
                 kind: 0,
                 flags: 0,
                 sequence: 0,
-                port_id: 0,
+                port_id: PortId::kernel(),
             },
-            payload: NetlinkPayload::None,
-        };
+            NetlinkPayload::None,
+        );
         let mut bytes = [0u8; 16];
         let written = message.to_array(&mut bytes);
 
         // Assert that we only wrote 16 bytes, but header says its 17.
         assert_eq!(written, 16);
-        match NetlinkMessage::from(&bytes) {
-            Err(NetlinkParseError::MessageIncomplete) => assert!(true),
-            _ => assert!(false),
-        }
+        assert!(matches!(
+            NetlinkMessage::from(&bytes),
+            Err(NetlinkParseError::MessageIncomplete)
+        ));
     }
 
     #[test]
     fn valid_netlink_message() {
-        let message = NetlinkMessage {
-            header: NetlinkHeader {
+        let message = NetlinkMessage::new(
+            NetlinkHeader {
                 length: 16,
                 kind: libc::NLMSG_ERROR as u16,
                 flags: libc::NLM_F_CREATE as u16,
                 sequence: 1,
-                port_id: 123,
+                port_id: PortId(123),
             },
-            payload: NetlinkPayload::None,
-        };
+            NetlinkPayload::None,
+        );
         let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
         message.to_array(&mut bytes);
 
@@ -223,9 +1001,460 @@ mod message_test {
                 assert_eq!(message.header.kind, libc::NLMSG_ERROR as u16);
                 assert_eq!(message.header.flags, libc::NLM_F_CREATE as u16);
                 assert_eq!(message.header.sequence, 1);
-                assert_eq!(message.header.port_id, 123);
+                assert_eq!(message.header.port_id, PortId(123));
             }
-            _ => assert!(false),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn protocol_aware_parsing() {
+        // `RTM_NEWLINK` and `GENL_ID_CTRL` share the same value.
+        assert_eq!(libc::RTM_NEWLINK, libc::GENL_ID_CTRL as u16);
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&32u32.to_ne_bytes()); // Length
+        bytes.extend_from_slice(&libc::RTM_NEWLINK.to_ne_bytes()); // Type
+        bytes.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Sequence
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+
+        // `ifinfomsg` for route or `genlmsghdr` and two attributes for generic.
+        bytes.extend_from_slice(&[0x01, 0x02, 0x00, 0x00]);
+        bytes.extend_from_slice(&4u16.to_ne_bytes());
+        bytes.extend_from_slice(&1u16.to_ne_bytes());
+        bytes.extend_from_slice(&8u16.to_ne_bytes());
+        bytes.extend_from_slice(&2u16.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 4]);
+
+        assert!(matches!(
+            NetlinkMessage::from_protocol(&bytes, NetlinkProtocol::Route),
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Link(_)),
+                ..
+            })
+        ));
+        match NetlinkMessage::from_protocol(&bytes, NetlinkProtocol::Generic) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Generic(generic),
+                ..
+            }) => {
+                assert_eq!(generic.command, 0x01);
+                assert_eq!(generic.version, 0x02);
+                assert_eq!(generic.attributes.len(), 2);
+            }
+            _ => unreachable!(),
+        }
+        assert!(matches!(
+            NetlinkMessage::from_protocol(&bytes, NetlinkProtocol::Audit),
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Unknown(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_error_display() {
+        assert_eq!(
+            NetlinkParseError::MessageTooSmall.to_string(),
+            "netlink message smaller than header"
+        );
+        assert_eq!(
+            NetlinkParseError::MessageIncomplete.to_string(),
+            "netlink message truncated"
+        );
+        assert_eq!(
+            NetlinkParseError::InvalidAttributeLength.to_string(),
+            "netlink attribute length invalid for its type"
+        );
+    }
+
+    #[test]
+    fn message_as_bytes() {
+        let message = NetlinkMessage::new(
+            NetlinkHeader {
+                length: 20,
+                kind: libc::RTM_NEWNEIGH,
+                flags: 0,
+                sequence: 7,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::None,
+        );
+        assert!(message.as_bytes().is_empty());
+
+        // Trailing bytes past the message length are not included.
+        let mut bytes = [0xffu8; 32];
+        message.to_array(&mut bytes);
+        let parsed = NetlinkMessage::from(&bytes).unwrap();
+        assert_eq!(parsed.as_bytes(), &bytes[..20]);
+    }
+
+    fn neighbor_message(sequence: u32) -> [u8; 20] {
+        let mut bytes = [0u8; 20];
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: 20,
+                kind: libc::RTM_NEWNEIGH,
+                flags: 0,
+                sequence,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::None,
+        )
+        .to_array(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn iterator_partial_header() {
+        let mut bytes = neighbor_message(1).to_vec();
+        bytes.extend_from_slice(&neighbor_message(2));
+        // Short read: only part of the third header arrived.
+        bytes.extend_from_slice(&neighbor_message(3)[..10]);
+
+        let sequences: Vec<u32> = NetlinkMessageIterator::new(&bytes)
+            .map(|message| message.unwrap().header.sequence)
+            .collect();
+        assert_eq!(sequences, vec![1, 2]);
+    }
+
+    #[test]
+    fn consumed_length() {
+        // 3 bytes payload: 19 bytes message padded to 20.
+        let mut bytes = message(libc::RTM_NEWNEIGH, 1, &[0x01, 0x02, 0x03]);
+        bytes.push(0);
+        bytes.extend_from_slice(&neighbor_message(2));
+
+        let (message, length) = NetlinkMessage::from_with_len(&bytes).unwrap();
+        assert_eq!(message.header.length, 19);
+        assert_eq!(length, 20);
+        let (message, _) = NetlinkMessage::from_with_len(&bytes[length..]).unwrap();
+        assert_eq!(message.header.sequence, 2);
+    }
+
+    #[test]
+    fn iterator_zero_padding() {
+        let mut bytes = neighbor_message(1).to_vec();
+        bytes.extend_from_slice(&neighbor_message(2));
+        bytes.extend_from_slice(&[0; 32]);
+
+        let mut iterator = NetlinkMessageIterator::new(&bytes);
+        assert_eq!(iterator.next().unwrap().unwrap().header.sequence, 1);
+        assert_eq!(iterator.next().unwrap().unwrap().header.sequence, 2);
+        assert!(iterator.next().is_none());
+        assert!(iterator.next().is_none());
+    }
+
+    #[test]
+    fn iterator_truncated_message() {
+        let mut bytes = neighbor_message(1).to_vec();
+        // Header is present, but the message is missing its last 2 bytes.
+        bytes.extend_from_slice(&neighbor_message(2)[..18]);
+
+        let mut iterator = NetlinkMessageIterator::new(&bytes);
+        assert_eq!(iterator.next().unwrap().unwrap().header.sequence, 1);
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(NetlinkParseError::MessageIncomplete))
+        ));
+        assert!(iterator.next().is_none());
+    }
+
+    #[test]
+    fn malformed_input() {
+        // Declared length smaller than the header.
+        let mut bytes = neighbor_message(1);
+        bytes[..4].copy_from_slice(&4u32.to_ne_bytes());
+        let mut iterator = NetlinkMessageIterator::new(&bytes);
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(NetlinkParseError::MessageTooSmall))
+        ));
+        assert!(iterator.next().is_none());
+
+        // Declared length past the buffer end.
+        bytes[..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+        assert!(matches!(
+            NetlinkMessage::from(&bytes),
+            Err(NetlinkParseError::MessageIncomplete)
+        ));
+
+        let link = |attributes: &[u8]| {
+            let mut payload = vec![0u8; 16];
+            payload.extend_from_slice(attributes);
+            message(libc::RTM_NEWLINK, 1, &payload)
+        };
+        // Attribute length smaller than the attribute header, attribute
+        // length past the message end and nested attribute length past its
+        // parent: the payload is kept undecoded or the accessor fails.
+        for attributes in [
+            &[0x02, 0x00, 0x03, 0x00][..],
+            &[0xff, 0xff, 0x03, 0x00, b'l', b'o', 0x00, 0x00],
+            &[
+                0x0c, 0x00, 0x12, 0x00, 0x40, 0x00, 0x01, 0x00, b'v', b'l', b'a', b'n',
+            ],
+        ] {
+            match NetlinkMessage::from(&link(attributes)).unwrap().payload {
+                NetlinkPayload::Unknown(payload) => {
+                    assert_eq!(payload.len(), 16 + attributes.len())
+                }
+                NetlinkPayload::Route(MessageType::Link(link)) => {
+                    assert_eq!(link.info_kind(), None);
+                    assert_eq!(link.vlan_info(), None);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn rewrite_header() {
+        let mut bytes = neighbor_message(1);
+        let mut header = NetlinkMessage::from(&bytes).unwrap().header;
+        header.sequence = 42;
+        header.flags |= libc::NLM_F_REQUEST as u16;
+        NetlinkMessage::rewrite_header(&mut bytes, &header).unwrap();
+
+        let message = NetlinkMessage::from(&bytes).unwrap();
+        assert_eq!(message.header.sequence, 42);
+        assert_eq!(message.header.flags, libc::NLM_F_REQUEST as u16);
+        assert_eq!(message.header.length, 20);
+
+        assert!(matches!(
+            NetlinkMessage::rewrite_header(&mut bytes[..15], &header),
+            Err(NetlinkParseError::MessageTooSmall)
+        ));
+    }
+
+    #[test]
+    fn wire_layout() {
+        let header = NetlinkHeader {
+            length: 32,
+            kind: libc::RTM_NEWLINK,
+            flags: 0x0102,
+            sequence: 0x03040506,
+            port_id: PortId(0x0708090a),
+        };
+        let link = route::Link {
+            message: route::LinkMessage {
+                family: route::family::UNSPEC,
+                kind: libc::ARPHRD_ETHER,
+                index: 0x0b0c0d0e,
+                flags: 0x0f101112,
+                change: 0x13141516,
+            },
+            attributes: vec![],
+            unknown: vec![],
+        };
+
+        let mut expected = vec![];
+        expected.extend_from_slice(&32u32.to_ne_bytes());
+        expected.extend_from_slice(&libc::RTM_NEWLINK.to_ne_bytes());
+        expected.extend_from_slice(&0x0102u16.to_ne_bytes());
+        expected.extend_from_slice(&0x03040506u32.to_ne_bytes());
+        expected.extend_from_slice(&0x0708090au32.to_ne_bytes());
+        expected.extend_from_slice(&[route::family::UNSPEC, 0]);
+        expected.extend_from_slice(&libc::ARPHRD_ETHER.to_ne_bytes());
+        expected.extend_from_slice(&0x0b0c0d0ei32.to_ne_bytes());
+        expected.extend_from_slice(&0x0f101112u32.to_ne_bytes());
+        expected.extend_from_slice(&0x13141516u32.to_ne_bytes());
+
+        let mut bytes = [0u8; 64];
+        let length = NetlinkMessage::new(header, NetlinkPayload::Route(MessageType::Link(link)))
+            .to_array(&mut bytes);
+        assert_eq!(&bytes[..length], expected.as_slice());
+    }
+
+    fn message(kind: u16, sequence: u32, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + payload.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&sequence.to_ne_bytes());
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn category() {
+        let mut error = (-libc::EPERM).to_ne_bytes().to_vec();
+        error.extend(message(libc::RTM_NEWLINK, 3, &[]));
+        let bytes = message(NLMSG_ERROR, 3, &error);
+        assert_eq!(
+            NetlinkMessage::from(&bytes).unwrap().category(),
+            MessageCategory::Error
+        );
+
+        let mut ack = 0i32.to_ne_bytes().to_vec();
+        ack.extend(message(libc::RTM_NEWLINK, 3, &[]));
+        let bytes = message(NLMSG_ERROR, 3, &ack);
+        assert_eq!(
+            NetlinkMessage::from(&bytes).unwrap().category(),
+            MessageCategory::Reply
+        );
+
+        let bytes = message(NLMSG_DONE, 3, &0i32.to_ne_bytes());
+        assert_eq!(
+            NetlinkMessage::from(&bytes).unwrap().category(),
+            MessageCategory::Done
+        );
+
+        let bytes = message(libc::RTM_NEWNEIGH, 0, &[]);
+        assert_eq!(
+            NetlinkMessage::from(&bytes).unwrap().category(),
+            MessageCategory::Notification
+        );
+
+        let bytes = message(libc::RTM_NEWNEIGH, 3, &[]);
+        assert_eq!(
+            NetlinkMessage::from(&bytes).unwrap().category(),
+            MessageCategory::Reply
+        );
+    }
+
+    #[test]
+    fn category_for() {
+        // Notification of the request sequence 3 from port 200.
+        let mut bytes = message(libc::RTM_NEWNEIGH, 3, &[]);
+        bytes[12..16].copy_from_slice(&200u32.to_ne_bytes());
+        let notification = NetlinkMessage::from(&bytes).unwrap();
+        assert_eq!(notification.category(), MessageCategory::Reply);
+        assert_eq!(
+            notification.category_for(PortId(100)),
+            MessageCategory::Notification
+        );
+        assert_eq!(
+            notification.category_for(PortId(200)),
+            MessageCategory::Reply
+        );
+
+        let mut error = (-libc::EPERM).to_ne_bytes().to_vec();
+        error.extend(message(libc::RTM_NEWLINK, 3, &[]));
+        let bytes = message(NLMSG_ERROR, 3, &error);
+        assert_eq!(
+            NetlinkMessage::from(&bytes)
+                .unwrap()
+                .category_for(PortId(100)),
+            MessageCategory::Error
+        );
+    }
+
+    #[test]
+    fn get_route_to() {
+        let message = NetlinkMessage::get_route_to(IpAddr::from([8, 8, 8, 8]), 7);
+        assert_eq!(message.header.kind, libc::RTM_GETROUTE);
+        assert_eq!(message.header.flags, libc::NLM_F_REQUEST as u16);
+        assert_eq!(message.header.sequence, 7);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 12 + 8);
+        match NetlinkMessage::from(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Route(route)),
+                ..
+            }) => {
+                assert_eq!(route.message.family, route::family::INET);
+                assert_eq!(route.message.destination_prefix_length, 32);
+                assert_eq!(route.destination(), Some(IpAddr::from([8, 8, 8, 8])));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "socket")]
+    fn get_route_to_public_address() {
+        use crate::connection::NetlinkConnection;
+        use crate::socket::{NetlinkProtocol, NetlinkSocket};
+
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        let destination = IpAddr::from([8, 8, 8, 8]);
+        let mut routes = vec![];
+        let result = NetlinkConnection::from_transport(&socket).request(
+            NetlinkMessage::get_route_to(destination, 0),
+            |message| {
+                if let NetlinkPayload::Route(MessageType::Route(route)) = message.payload {
+                    routes.push(route);
+                }
+            },
+        );
+        if let Err(error) = result {
+            assert_eq!(error.raw_os_error(), Some(libc::ENETUNREACH));
+            eprintln!("skipping: no route to {}", destination);
+            return;
+        }
+
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].destination(), Some(destination));
+        assert!(routes[0].oif().is_some());
+    }
+
+    #[test]
+    fn payload_accessors() {
+        let mut bytes = message(libc::RTM_NEWNEIGH, 1, &[0x01, 0x02, 0x03, 0x04]);
+        let message = NetlinkMessage::from(&bytes).unwrap();
+        assert_eq!(message.payload.as_unknown(), Some(&bytes[16..]));
+        assert_eq!(message.payload.len(), 4);
+        assert!(!message.payload.is_empty());
+
+        bytes = vec![0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = route_builder::get_links_request()
+            .build()
+            .to_array(&mut bytes);
+        let message = NetlinkMessage::from(&bytes[..length]).unwrap();
+        assert!(matches!(
+            message.payload,
+            NetlinkPayload::Route(MessageType::Link(_))
+        ));
+        assert_eq!(message.payload.as_unknown(), None);
+        assert_eq!(message.payload.len(), length - 16);
+        assert_eq!(message.payload_len(), length - 16);
+    }
+
+    #[test]
+    fn payload_len_error_echo() {
+        // Without `NETLINK_CAP_ACK` the kernel echoes the whole request.
+        let mut bytes = vec![0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = route_builder::LinkBuilder::set_link(2)
+            .alias("uplink")
+            .build()
+            .to_array(&mut bytes);
+        let mut error = (-libc::EPERM).to_ne_bytes().to_vec();
+        error.extend_from_slice(&bytes[..length]);
+
+        let bytes = message(NLMSG_ERROR, 3, &error);
+        let message = NetlinkMessage::from(&bytes).unwrap();
+        assert!(matches!(message.payload, NetlinkPayload::Error(_)));
+        assert_eq!(message.payload.len(), 4 + 16);
+        assert_eq!(message.payload_len(), 4 + length);
+    }
+
+    #[test]
+    fn payload_len_separate_unknown() {
+        let mut bytes = vec![0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = route_builder::get_links_request()
+            .build()
+            .to_array(&mut bytes);
+        // Attribute type without typed representation.
+        let unknown = [8u16.to_ne_bytes(), 0x7fffu16.to_ne_bytes()].concat();
+        bytes[length..length + 4].copy_from_slice(&unknown);
+        bytes[length + 4..length + 8].copy_from_slice(&1u32.to_ne_bytes());
+        let length = length + 8;
+        bytes[..4].copy_from_slice(&(length as u32).to_ne_bytes());
+
+        let options = ParseOptions {
+            separate_unknown: true,
+            ..Default::default()
+        };
+        let message = NetlinkMessage::from_with_options(&bytes[..length], options).unwrap();
+        match &message.payload {
+            NetlinkPayload::Route(MessageType::Link(link)) => assert_eq!(link.unknown.len(), 1),
+            _ => unreachable!(),
         }
+        assert_eq!(message.payload_len(), length - 16);
     }
 }
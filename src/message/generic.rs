@@ -0,0 +1,248 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use super::{
+    route_attribute::{Attribute, AttributeValue},
+    NetlinkHeader, NetlinkMessage, NetlinkParseError, NetlinkParseResult, NetlinkPayload,
+    PacketParser, PacketWriter, PortId,
+};
+use std::mem;
+
+/// Generic netlink header size (`struct genlmsghdr`).
+pub const GENERIC_HEADER_SIZE: usize = 4;
+
+/// Generic netlink message: header (`struct genlmsghdr`) followed by the
+/// family attributes.
+///
+/// The netlink header type holds the family ID (e.g. `GENL_ID_CTRL`).
+pub struct GenericMessage {
+    /// Family specific command (e.g. `CTRL_CMD_NEWFAMILY`).
+    pub command: u8,
+    /// Family specific interface version.
+    pub version: u8,
+    /// Family specific attributes.
+    pub attributes: Vec<Attribute>,
+}
+
+impl GenericMessage {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<GenericMessage> {
+        if (parser.remaining() as usize) < GENERIC_HEADER_SIZE {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let command = parser.read_u8();
+        let version = parser.read_u8();
+        let _reserved = parser.read_u16();
+        let mut attributes = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+            attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                parser, length, kind,
+            )?));
+        }
+
+        Ok(GenericMessage {
+            command,
+            version,
+            attributes,
+        })
+    }
+
+    /// Payload of the first attribute of type `kind`.
+    pub fn attribute(&self, kind: u16) -> Option<&[u8]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Unknown(value) if value.kind == kind => Some(value.value.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Family ID announced by the controller (`CTRL_ATTR_FAMILY_ID`).
+    pub fn family_id(&self) -> Option<u16> {
+        self.attribute(libc::CTRL_ATTR_FAMILY_ID as u16)?
+            .try_into()
+            .ok()
+            .map(u16::from_ne_bytes)
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.command);
+        writter.write_u8(self.version);
+        writter.write_u16(0);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+/// Generic netlink request builder, the message type is the family ID
+/// (see [`get_family_request`] to resolve family names).
+pub struct GenericBuilder {
+    family: u16,
+    flags: u16,
+    message: GenericMessage,
+}
+
+impl GenericBuilder {
+    /// Request `command` (interface `version`) to the family `family`.
+    pub fn new(family: u16, command: u8, version: u8) -> GenericBuilder {
+        GenericBuilder {
+            family,
+            flags: libc::NLM_F_REQUEST as u16,
+            message: GenericMessage {
+                command,
+                version,
+                attributes: vec![],
+            },
+        }
+    }
+
+    /// Append a family specific attribute.
+    pub fn attribute(mut self, attribute: Attribute) -> GenericBuilder {
+        self.message.attributes.push(attribute);
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + GENERIC_HEADER_SIZE
+            + self
+                .message
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.family,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Generic(self.message),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Ask the controller (`CTRL_CMD_GETFAMILY`) for the family called `name`,
+/// the reply carries the ID in [`GenericMessage::family_id`].
+pub fn get_family_request(name: &str) -> GenericBuilder {
+    GenericBuilder::new(libc::GENL_ID_CTRL as u16, libc::CTRL_CMD_GETFAMILY as u8, 1).attribute(
+        Attribute::String(AttributeValue::<String> {
+            length: 0,
+            kind: libc::CTRL_ATTR_FAMILY_NAME as u16,
+            value: name.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod generic_test {
+    use crate::message::generic::get_family_request;
+    use crate::message::*;
+
+    #[test]
+    fn controller_new_family() {
+        let mut message = vec![];
+        message.extend_from_slice(&40u32.to_ne_bytes()); // Length
+        message.extend_from_slice(&(libc::GENL_ID_CTRL as u16).to_ne_bytes()); // Type
+        message.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        message.extend_from_slice(&1u32.to_ne_bytes()); // Sequence
+        message.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        message.push(libc::CTRL_CMD_NEWFAMILY as u8); // Command
+        message.push(2); // Version
+        message.extend_from_slice(&0u16.to_ne_bytes()); // Reserved
+        message.extend_from_slice(&11u16.to_ne_bytes());
+        message.extend_from_slice(&(libc::CTRL_ATTR_FAMILY_NAME as u16).to_ne_bytes());
+        message.extend_from_slice(b"nlctrl\0\0"); // Name and padding
+        message.extend_from_slice(&6u16.to_ne_bytes());
+        message.extend_from_slice(&(libc::CTRL_ATTR_FAMILY_ID as u16).to_ne_bytes());
+        message.extend_from_slice(&(libc::GENL_ID_CTRL as u16).to_ne_bytes());
+        message.extend_from_slice(&[0x00, 0x00]); // Padding
+        assert_eq!(message.len(), 40);
+
+        match NetlinkMessage::from_generic(&message) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Generic(generic),
+                ..
+            }) => {
+                assert_eq!(generic.command, libc::CTRL_CMD_NEWFAMILY as u8);
+                assert_eq!(generic.version, 2);
+                assert_eq!(
+                    generic.attribute(libc::CTRL_ATTR_FAMILY_NAME as u16),
+                    Some(&b"nlctrl\0"[..])
+                );
+                assert_eq!(
+                    generic.attribute(libc::CTRL_ATTR_FAMILY_ID as u16),
+                    Some(&(libc::GENL_ID_CTRL as u16).to_ne_bytes()[..])
+                );
+                assert_eq!(generic.family_id(), Some(libc::GENL_ID_CTRL as u16));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn get_family() {
+        let message = get_family_request("ethtool").build();
+        assert_eq!(message.header.kind, libc::GENL_ID_CTRL as u16);
+        assert_eq!(message.header.length, 16 + 4 + 12);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 4 + 12);
+
+        match NetlinkMessage::from_generic(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Generic(generic),
+                ..
+            }) => {
+                assert_eq!(generic.command, libc::CTRL_CMD_GETFAMILY as u8);
+                assert_eq!(
+                    generic.attribute(libc::CTRL_ATTR_FAMILY_NAME as u16),
+                    Some(&b"ethtool\0"[..])
+                );
+                assert_eq!(generic.family_id(), None);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -21,25 +21,34 @@
 // OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
 // SUCH DAMAGE.
 
+use super::ParseOptions;
 use std::io::{Cursor, Read};
 
 pub struct PacketParser<'a> {
-    bytes: &'a [u8],
     cursor: Cursor<&'a [u8]>,
     total: u64,
     netlink_length: u32,
+    options: ParseOptions,
 }
 
-impl PacketParser<'_> {
-    pub fn new(input_buffer: &[u8]) -> PacketParser {
+impl<'a> PacketParser<'a> {
+    pub fn new(input_buffer: &'a [u8]) -> PacketParser<'a> {
         PacketParser {
-            bytes: input_buffer,
             cursor: Cursor::new(input_buffer),
             total: input_buffer.len() as u64,
             netlink_length: 0,
+            options: ParseOptions::default(),
         }
     }
 
+    /// Point the parser at `input_buffer` from its start, keeping the
+    /// parsing options.
+    pub fn reset(&mut self, input_buffer: &'a [u8]) {
+        self.cursor = Cursor::new(input_buffer);
+        self.total = input_buffer.len() as u64;
+        self.netlink_length = 0;
+    }
+
     pub fn remaining(&self) -> u64 {
         self.total - self.cursor.position()
     }
@@ -56,39 +65,115 @@ impl PacketParser<'_> {
         self.netlink_length
     }
 
+    pub fn set_options(&mut self, options: ParseOptions) {
+        self.options = options
+    }
+
+    pub fn options(&self) -> ParseOptions {
+        self.options
+    }
+
     pub fn read_u8(&mut self) -> u8 {
         let mut buffer = [0u8; 1];
-        self.cursor.read(&mut buffer).unwrap();
+        let _ = self.cursor.read_exact(&mut buffer);
         buffer[0]
     }
 
     pub fn read_u16(&mut self) -> u16 {
         let mut buffer = [0u8; 2];
-        self.cursor.read(&mut buffer).unwrap();
+        let _ = self.cursor.read_exact(&mut buffer);
         u16::from_ne_bytes(buffer)
     }
 
     pub fn read_i32(&mut self) -> i32 {
         let mut buffer = [0u8; 4];
-        self.cursor.read(&mut buffer).unwrap();
+        let _ = self.cursor.read_exact(&mut buffer);
         i32::from_ne_bytes(buffer)
     }
 
     pub fn read_u32(&mut self) -> u32 {
         let mut buffer = [0u8; 4];
-        self.cursor.read(&mut buffer).unwrap();
+        let _ = self.cursor.read_exact(&mut buffer);
         u32::from_ne_bytes(buffer)
     }
 
+    pub fn read_u64(&mut self) -> u64 {
+        let mut buffer = [0u8; 8];
+        let _ = self.cursor.read_exact(&mut buffer);
+        u64::from_ne_bytes(buffer)
+    }
+
     pub fn read_mac(&mut self) -> [u8; 6] {
         let mut buffer = [0u8; 6];
-        self.cursor.read(&mut buffer).unwrap();
+        let _ = self.cursor.read_exact(&mut buffer);
         buffer
     }
 
     pub fn read_vec(&mut self, amount: usize) -> Vec<u8> {
         let mut bytes = vec![0u8; amount];
-        self.cursor.read(&mut bytes).unwrap();
+        let _ = self.cursor.read_exact(&mut bytes);
         bytes
     }
+
+    pub fn read_slice(&mut self, amount: usize) -> &'a [u8] {
+        let bytes: &'a [u8] = self.cursor.get_ref();
+        let start = self.cursor.position() as usize;
+        let end = start + amount.min(self.remaining() as usize);
+        self.cursor.set_position(end as u64);
+        &bytes[start..end]
+    }
+
+    pub fn skip(&mut self, amount: usize) {
+        let amount = amount.min(self.remaining() as usize);
+        self.cursor
+            .set_position(self.cursor.position() + amount as u64);
+    }
+
+    /// Move the cursor to the next multiple of `boundary` (e.g. attribute
+    /// padding), without going past the end of the buffer.
+    pub fn align_to(&mut self, boundary: usize) {
+        let position = self.cursor.position() as usize;
+        self.skip(position.next_multiple_of(boundary) - position);
+    }
+}
+
+#[cfg(test)]
+mod packet_parser_test {
+    use super::*;
+
+    #[test]
+    fn reset() {
+        let first = 0x01020304u32.to_ne_bytes();
+        let second = [0xaa, 0xbb];
+        let mut parser = PacketParser::new(&first);
+        parser.set_netlink_length(4);
+        assert_eq!(parser.read_u16(), u16::from_ne_bytes([first[0], first[1]]));
+
+        parser.reset(&second);
+        assert_eq!(parser.position(), 0);
+        assert_eq!(parser.remaining(), 2);
+        assert_eq!(parser.get_netlink_length(), 0);
+        assert_eq!(parser.read_u16(), u16::from_ne_bytes(second));
+        assert_eq!(parser.remaining(), 0);
+
+        parser.reset(&first);
+        assert_eq!(parser.read_u32(), 0x01020304);
+    }
+
+    #[test]
+    fn align_to() {
+        let bytes = [0u8; 8];
+        for (position, aligned) in [(1, 4), (2, 4), (3, 4), (4, 4)] {
+            let mut parser = PacketParser::new(&bytes);
+            parser.skip(position);
+            parser.align_to(4);
+            assert_eq!(parser.position(), aligned);
+        }
+
+        let mut parser = PacketParser::new(&bytes[..6]);
+        parser.skip(5);
+        parser.align_to(4);
+        assert_eq!(parser.position(), 6);
+        assert_eq!(parser.remaining(), 0);
+    }
 }
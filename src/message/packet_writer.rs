@@ -29,7 +29,7 @@ pub struct PacketWriter<'a> {
 }
 
 impl PacketWriter<'_> {
-    pub fn new(input_buffer: &mut [u8]) -> PacketWriter {
+    pub fn new(input_buffer: &mut [u8]) -> PacketWriter<'_> {
         PacketWriter {
             input_buffer,
             total: 0,
@@ -41,30 +41,32 @@ impl PacketWriter<'_> {
     }
 
     pub fn write_u8(&mut self, value: u8) {
-        match self.input_buffer.write(&value.to_ne_bytes()) {
-            Ok(amount) => self.total += amount,
-            Err(_) => (),
+        if let Ok(amount) = self.input_buffer.write(&value.to_ne_bytes()) {
+            self.total += amount;
         }
     }
 
     pub fn write_u16(&mut self, value: u16) {
-        match self.input_buffer.write(&value.to_ne_bytes()) {
-            Ok(amount) => self.total += amount,
-            Err(_) => (),
+        if let Ok(amount) = self.input_buffer.write(&value.to_ne_bytes()) {
+            self.total += amount;
         }
     }
 
     pub fn write_i32(&mut self, value: i32) {
-        match self.input_buffer.write(&value.to_ne_bytes()) {
-            Ok(amount) => self.total += amount,
-            Err(_) => (),
+        if let Ok(amount) = self.input_buffer.write(&value.to_ne_bytes()) {
+            self.total += amount;
         }
     }
 
     pub fn write_u32(&mut self, value: u32) {
-        match self.input_buffer.write(&value.to_ne_bytes()) {
-            Ok(amount) => self.total += amount,
-            Err(_) => (),
+        if let Ok(amount) = self.input_buffer.write(&value.to_ne_bytes()) {
+            self.total += amount;
+        }
+    }
+
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        if let Ok(amount) = self.input_buffer.write(value) {
+            self.total += amount;
         }
     }
 }
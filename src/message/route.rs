@@ -22,14 +22,20 @@
 // SUCH DAMAGE.
 
 use super::{
-    packet_writer::PacketWriter, route_attribute::*, NetlinkParseError, NetlinkParseResult,
-    PacketParser,
+    packet_writer::PacketWriter, route_attribute::*, NetlinkHeader, NetlinkMessage,
+    NetlinkParseError, NetlinkParseResult, NetlinkPayload, PacketParser, PortId,
 };
+use std::fmt;
+use std::net::IpAddr;
 
 pub enum MessageType {
     Link(Link),
-    Address(AddressMessage),
-    Route(RouteMessage),
+    Address(Address),
+    Route(Route),
+    NextHop(NextHopObject),
+    NsId(NsId),
+    Stats(LinkStatsExtended),
+    Neighbor(Neighbor),
 }
 
 //
@@ -39,6 +45,8 @@ pub mod family {
     pub const UNSPEC: u8 = libc::AF_UNSPEC as u8;
     pub const INET: u8 = libc::AF_INET as u8;
     pub const INET6: u8 = libc::AF_INET6 as u8;
+    pub const MPLS: u8 = libc::AF_MPLS as u8;
+    pub const PACKET: u8 = libc::AF_PACKET as u8;
 }
 
 pub mod route_type {
@@ -62,6 +70,8 @@ pub mod protocol {
     pub const KERNEL: u8 = libc::RTPROT_KERNEL;
     pub const BOOT: u8 = libc::RTPROT_BOOT;
     pub const STATIC: u8 = libc::RTPROT_STATIC;
+    /// Router advertisement (not available in `libc`).
+    pub const RA: u8 = 9;
 }
 
 pub mod scope {
@@ -78,6 +88,224 @@ pub mod route_flags {
     pub const EQUALIZE: u32 = libc::RTM_F_EQUALIZE;
 }
 
+/// Route attribute types not available in `libc`
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/rtnetlink.h#L368)).
+pub mod route_attribute_type {
+    pub const VIA: u16 = 18;
+    pub const NEWDST: u16 = 19;
+    pub const PREF: u16 = 20;
+    pub const ENCAP_TYPE: u16 = 21;
+    pub const ENCAP: u16 = 22;
+    pub const EXPIRES: u16 = 23;
+    pub const PAD: u16 = 24;
+    pub const UID: u16 = 25;
+    pub const TTL_PROPAGATE: u16 = 26;
+    pub const IP_PROTO: u16 = 27;
+    pub const SPORT: u16 = 28;
+    pub const DPORT: u16 = 29;
+    pub const NH_ID: u16 = 30;
+}
+
+/// Light weight tunnel encapsulation types (`RTA_ENCAP_TYPE` values).
+pub mod encap_type {
+    pub const NONE: u16 = 0;
+    pub const MPLS: u16 = 1;
+    pub const IP: u16 = 2;
+    pub const ILA: u16 = 3;
+    pub const IP6: u16 = 4;
+    pub const SEG6: u16 = 5;
+    pub const BPF: u16 = 6;
+    pub const SEG6_LOCAL: u16 = 7;
+    pub const RPL: u16 = 8;
+    pub const IOAM6: u16 = 9;
+    pub const XFRM: u16 = 10;
+}
+
+/// MPLS encapsulation attribute types (nested in `RTA_ENCAP`).
+pub mod mpls_iptunnel {
+    pub const DST: u16 = 1;
+    pub const TTL: u16 = 2;
+}
+
+/// Route message types not available in `libc`
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/rtnetlink.h#L163)).
+pub mod message_type {
+    pub const NEWNEXTHOP: u16 = 104;
+    pub const DELNEXTHOP: u16 = 105;
+    pub const GETNEXTHOP: u16 = 106;
+    pub const NEWSTATS: u16 = 92;
+    pub const GETSTATS: u16 = 94;
+    pub const NEWLINKPROP: u16 = 108;
+    pub const DELLINKPROP: u16 = 109;
+}
+
+/// Nexthop object attribute types
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/nexthop.h#L30)).
+pub mod nexthop_attribute {
+    pub const ID: u16 = 1;
+    pub const GROUP: u16 = 2;
+    pub const GROUP_TYPE: u16 = 3;
+    pub const BLACKHOLE: u16 = 4;
+    pub const OIF: u16 = 5;
+    pub const GATEWAY: u16 = 6;
+    pub const ENCAP_TYPE: u16 = 7;
+    pub const ENCAP: u16 = 8;
+    pub const GROUPS: u16 = 9;
+    pub const MASTER: u16 = 10;
+    pub const FDB: u16 = 11;
+}
+
+/// VLAN attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h#L564)).
+pub mod vlan_attribute {
+    pub const ID: u16 = 1;
+    pub const FLAGS: u16 = 2;
+    pub const EGRESS_QOS: u16 = 3;
+    pub const INGRESS_QOS: u16 = 4;
+    pub const PROTOCOL: u16 = 5;
+}
+
+/// GRE tunnel attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_tunnel.h)).
+pub mod gre_attribute {
+    pub const LINK: u16 = 1;
+    pub const IFLAGS: u16 = 2;
+    pub const OFLAGS: u16 = 3;
+    pub const IKEY: u16 = 4;
+    pub const OKEY: u16 = 5;
+    pub const LOCAL: u16 = 6;
+    pub const REMOTE: u16 = 7;
+    pub const TTL: u16 = 8;
+    pub const TOS: u16 = 9;
+}
+
+/// IP tunnel attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_tunnel.h)).
+pub mod iptun_attribute {
+    pub const LINK: u16 = 1;
+    pub const LOCAL: u16 = 2;
+    pub const REMOTE: u16 = 3;
+    pub const TTL: u16 = 4;
+    pub const TOS: u16 = 5;
+}
+
+/// veth attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/veth.h)).
+pub mod veth_attribute {
+    /// Peer interface: `struct ifinfomsg` followed by its attributes.
+    pub const PEER: u16 = 1;
+}
+
+/// Bond attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h)).
+pub mod bond_attribute {
+    pub const MODE: u16 = 1;
+    pub const ACTIVE_SLAVE: u16 = 2;
+    pub const MIIMON: u16 = 3;
+    pub const UPDELAY: u16 = 4;
+    pub const DOWNDELAY: u16 = 5;
+}
+
+/// Bridge attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h)).
+pub mod bridge_attribute {
+    pub const FORWARD_DELAY: u16 = 1;
+    pub const HELLO_TIME: u16 = 2;
+    pub const MAX_AGE: u16 = 3;
+    pub const AGEING_TIME: u16 = 4;
+    pub const STP_STATE: u16 = 5;
+    pub const PRIORITY: u16 = 6;
+    pub const VLAN_FILTERING: u16 = 7;
+    pub const VLAN_PROTOCOL: u16 = 8;
+}
+
+/// Bond modes (`IFLA_BOND_MODE` values)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_bonding.h)).
+pub mod bond_mode {
+    pub const BALANCE_RR: u8 = 0;
+    pub const ACTIVE_BACKUP: u8 = 1;
+    pub const BALANCE_XOR: u8 = 2;
+    pub const BROADCAST: u8 = 3;
+    pub const IEEE_8023AD: u8 = 4;
+    pub const BALANCE_TLB: u8 = 5;
+    pub const BALANCE_ALB: u8 = 6;
+}
+
+/// TUN/TAP attribute types (nested in `IFLA_INFO_DATA`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h)).
+pub mod tun_attribute {
+    pub const OWNER: u16 = 1;
+    pub const GROUP: u16 = 2;
+    pub const TYPE: u16 = 3;
+    pub const PI: u16 = 4;
+    pub const VNET_HDR: u16 = 5;
+    pub const PERSIST: u16 = 6;
+    pub const MULTI_QUEUE: u16 = 7;
+    pub const NUM_QUEUES: u16 = 8;
+    pub const NUM_DISABLED_QUEUES: u16 = 9;
+}
+
+/// Interface statistics attribute types (`RTM_NEWSTATS`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h)).
+pub mod stats_attribute {
+    pub const LINK_64: u16 = 1;
+    pub const LINK_XSTATS: u16 = 2;
+    pub const LINK_XSTATS_SLAVE: u16 = 3;
+    pub const LINK_OFFLOAD_XSTATS: u16 = 4;
+    pub const AF_SPEC: u16 = 5;
+
+    /// Request filter bit of the attribute `kind` (`IFLA_STATS_FILTER_BIT`).
+    pub const fn filter_bit(kind: u16) -> u32 {
+        1 << (kind - 1)
+    }
+}
+
+/// `AF_INET` attribute types (nested in `IFLA_AF_SPEC`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h)).
+pub mod inet_attribute {
+    pub const CONF: u16 = 1;
+}
+
+/// IPv4 interface configuration indexes (`IPV4_DEVCONF_*` in
+/// `IFLA_INET_CONF`)
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/ip.h)).
+pub mod ipv4_devconf {
+    pub const FORWARDING: usize = 1;
+    pub const MC_FORWARDING: usize = 2;
+    pub const PROXY_ARP: usize = 3;
+    pub const ACCEPT_REDIRECTS: usize = 4;
+    pub const SECURE_REDIRECTS: usize = 5;
+    pub const SEND_REDIRECTS: usize = 6;
+    pub const SHARED_MEDIA: usize = 7;
+    pub const RP_FILTER: usize = 8;
+    pub const ACCEPT_SOURCE_ROUTE: usize = 9;
+    pub const BOOTP_RELAY: usize = 10;
+    pub const LOG_MARTIANS: usize = 11;
+}
+
+/// Address attribute types not available in `libc`
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_addr.h)).
+pub mod address_attribute {
+    /// Full address flags (`u32`), supersedes `ifa_flags`.
+    pub const FLAGS: u16 = 8;
+    pub const RT_PRIORITY: u16 = 9;
+    pub const TARGET_NETNSID: u16 = 10;
+    pub const PROTO: u16 = 11;
+}
+
+/// Network namespace ID attribute types
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/net_namespace.h#L16)).
+pub mod nsid_attribute {
+    pub const NSID: u16 = 1;
+    pub const PID: u16 = 2;
+    pub const FD: u16 = 3;
+    pub const TARGET_NSID: u16 = 4;
+    pub const CURRENT_NSID: u16 = 5;
+}
+
+/// Namespace ID not assigned to the namespace (`NETNSA_NSID_NOT_ASSIGNED`).
+pub const NSID_NOT_ASSIGNED: i32 = -1;
+
 //
 // Struct definitions
 //
@@ -91,9 +319,164 @@ pub struct LinkMessage {
     pub change: u32,
 }
 
+// `struct ifinfomsg` layout
+// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/rtnetlink.h)).
+const _: () = assert!(std::mem::size_of::<LinkMessage>() == 16);
+const _: () = assert!(std::mem::offset_of!(LinkMessage, kind) == 2);
+const _: () = assert!(std::mem::offset_of!(LinkMessage, index) == 4);
+
 pub struct Link {
     pub message: LinkMessage,
     pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+/// VLAN interface information (`IFLA_INFO_DATA` of "vlan" interfaces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VlanInfo {
+    pub id: u16,
+    /// Tag protocol (e.g. `ETH_P_8021Q` or `ETH_P_8021AD`).
+    pub protocol: u16,
+    /// `VLAN_FLAG_*` flags.
+    pub flags: u32,
+}
+
+impl fmt::Debug for Link {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Link")
+            .field("index", &self.index())
+            .field("family", &self.family())
+            .field("link_type", &self.link_type())
+            .field("flags", &format_args!("{:#x}", self.message.flags))
+            .field("name", &self.name())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Interface hardware type (`ifi_type`, `ARPHRD_*` values).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArpHardwareType {
+    Ether,
+    Loopback,
+    /// No link layer header (e.g. WireGuard or TUN interfaces).
+    None,
+    /// IPv4 over IPv4 tunnel.
+    Tunnel,
+    /// IPv6 over IPv4 tunnel.
+    Sit,
+    Ppp,
+    Other(u16),
+}
+
+impl From<u16> for ArpHardwareType {
+    fn from(kind: u16) -> ArpHardwareType {
+        match kind {
+            libc::ARPHRD_ETHER => ArpHardwareType::Ether,
+            libc::ARPHRD_LOOPBACK => ArpHardwareType::Loopback,
+            libc::ARPHRD_NONE => ArpHardwareType::None,
+            libc::ARPHRD_TUNNEL => ArpHardwareType::Tunnel,
+            libc::ARPHRD_SIT => ArpHardwareType::Sit,
+            libc::ARPHRD_PPP => ArpHardwareType::Ppp,
+            _ => ArpHardwareType::Other(kind),
+        }
+    }
+}
+
+impl fmt::Display for ArpHardwareType {
+    /// Type names as printed by `ip link`.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ArpHardwareType::Ether => "ether",
+            ArpHardwareType::Loopback => "loopback",
+            ArpHardwareType::None => "none",
+            ArpHardwareType::Tunnel => "ipip",
+            ArpHardwareType::Sit => "sit",
+            ArpHardwareType::Ppp => "ppp",
+            ArpHardwareType::Other(kind) => return write!(formatter, "{}", kind),
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// Bond interface information (`IFLA_INFO_DATA` of "bond" interfaces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BondInfo {
+    /// See [`bond_mode`] constants.
+    pub mode: u8,
+    /// Index of the active port, only for modes with one (e.g.
+    /// [`bond_mode::ACTIVE_BACKUP`]).
+    pub active_slave: Option<u32>,
+    /// MII link monitoring interval in milliseconds (zero when disabled).
+    pub miimon: u32,
+}
+
+/// Bridge information (`IFLA_INFO_DATA` of "bridge" interfaces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeInfo {
+    /// Spanning tree state: zero when disabled, 1 for kernel STP and 2 for
+    /// user space STP.
+    pub stp_state: u32,
+    /// Forward delay in hundredths of a second.
+    pub forward_delay: u32,
+    /// Bridge priority (STP bridge ID).
+    pub priority: Option<u16>,
+    /// VLAN filtering enabled.
+    pub vlan_filtering: bool,
+}
+
+/// TUN/TAP interface information (`IFLA_INFO_DATA` of "tun" interfaces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunInfo {
+    /// Owner user ID, `None` when any user may attach.
+    pub owner: Option<u32>,
+    /// Owner group ID, `None` when any group may attach.
+    pub group: Option<u32>,
+    /// Device type: `libc::IFF_TUN` or `libc::IFF_TAP`.
+    pub tun_type: u8,
+}
+
+/// Tunnel endpoints information (`IFLA_INFO_DATA` of "gre", "gretap",
+/// "ip6gre", "ip6gretap", "ipip", "sit" and "ip6tnl" interfaces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunnelInfo {
+    /// Local endpoint, `None` when any address is accepted.
+    pub local: Option<IpAddr>,
+    /// Remote endpoint, `None` for point to multipoint tunnels.
+    pub remote: Option<IpAddr>,
+    /// Time to live of the outer header, zero inherits the inner value.
+    pub ttl: u8,
+    /// GRE key expected on received packets (zero when not used).
+    pub input_key: u32,
+    /// GRE key set on sent packets (zero when not used).
+    pub output_key: u32,
+}
+
+/// Tunnel endpoint address, IPv4 or IPv6 by the payload size.
+fn tunnel_address(payload: &[u8]) -> Option<IpAddr> {
+    let address = match payload.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(payload).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(payload).ok()?),
+        _ => return None,
+    };
+    // Unspecified address: no endpoint.
+    (!address.is_unspecified()).then_some(address)
+}
+
+/// Per interface IPv4 configuration (`IFLA_INET_CONF`, the
+/// `net.ipv4.conf.<interface>` sysctls).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InetConf(Vec<i32>);
+
+impl InetConf {
+    /// Value of the configuration `index` (see [`ipv4_devconf`]
+    /// constants), `None` if the kernel didn't report it.
+    pub fn get(&self, index: usize) -> Option<i32> {
+        // The array starts at index one.
+        self.0.get(index.checked_sub(1)?).copied()
+    }
 }
 
 impl Link {
@@ -103,20 +486,63 @@ impl Link {
         }
 
         let family = parser.read_u8();
+        let _padding = parser.read_u8();
         let kind = parser.read_u16();
         let index = parser.read_i32();
         let flags = parser.read_u32();
         let change = parser.read_u32();
         let mut attributes = vec![];
+        let mut unknown = vec![];
 
         while parser.remaining() > 0 {
             let length = parser.read_u16();
             let kind = parser.read_u16();
 
             match kind {
-                libc::IFLA_ADDRESS => attributes.push(Attribute::Mac(AttributeValue::<Mac>::from(
+                libc::IFLA_ADDRESS | libc::IFLA_PERM_ADDRESS => attributes.push(Attribute::Mac(
+                    AttributeValue::<Mac>::from(parser, length, kind)?,
+                )),
+                libc::IFLA_MTU
+                | libc::IFLA_MIN_MTU
+                | libc::IFLA_MAX_MTU
+                | libc::IFLA_NUM_VF
+                | libc::IFLA_TXQLEN
+                | libc::IFLA_GROUP => attributes.push(Attribute::U32(AttributeValue::<u32>::from(
+                    parser, length, kind,
+                )?)),
+                libc::IFLA_OPERSTATE | libc::IFLA_LINKMODE | libc::IFLA_CARRIER => attributes.push(
+                    Attribute::U8(AttributeValue::<u8>::from(parser, length, kind)?),
+                ),
+                libc::IFLA_IFNAME | libc::IFLA_IFALIAS => attributes.push(Attribute::String(
+                    AttributeValue::<String>::from(parser, length, kind)?,
+                )),
+                libc::IFLA_VFINFO_LIST => {
+                    attributes.push(Attribute::VfInfoList(AttributeValue::<VfInfoList>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                libc::IFLA_NEW_IFINDEX | libc::IFLA_NEW_NETNSID => attributes.push(Attribute::I32(
+                    AttributeValue::<i32>::from(parser, length, kind)?,
+                )),
+                libc::IFLA_MAP => attributes.push(Attribute::IfMap(AttributeValue::<IfMap>::from(
                     parser, length, kind,
                 )?)),
+                libc::IFLA_LINKINFO | libc::IFLA_AF_SPEC => attributes.push(Attribute::Nested(
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?,
+                )),
+                // The kernel flags the property list with `NLA_F_NESTED`.
+                _ if kind & libc::NLA_TYPE_MASK as u16 == libc::IFLA_PROP_LIST => attributes.push(
+                    Attribute::Nested(AttributeValue::<Vec<u8>>::from(parser, length, kind)?),
+                ),
+                libc::IFLA_WIRELESS => {
+                    attributes.push(Attribute::Wireless(AttributeValue::<Vec<u8>>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
                 _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
                     parser, length, kind,
                 )?)),
@@ -131,19 +557,534 @@ impl Link {
                 flags,
                 change,
             },
-            attributes: attributes,
+            attributes,
+            unknown,
+        })
+    }
+
+    /// Call `visitor` with the type and payload of every attribute of the
+    /// interface message in `bytes` (netlink header included) without
+    /// building a [`Link`], for hot paths that only need a few
+    /// attributes.
+    pub fn for_each_attribute<'a, F>(bytes: &'a [u8], visitor: F) -> NetlinkParseResult<()>
+    where
+        F: FnMut(u16, &'a [u8]),
+    {
+        let offset = std::mem::size_of::<NetlinkHeader>() + std::mem::size_of::<LinkMessage>();
+        if bytes.len() < offset {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        visit_attributes(&bytes[offset..], visitor)
+    }
+
+    fn attribute_u32(&self, kind: u16) -> Option<u32> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U32(value) if value.kind == kind => Some(value.value),
+                _ => None,
+            })
+    }
+
+    /// Interface MTU (`IFLA_MTU`).
+    pub fn mtu(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_MTU)
+    }
+
+    /// Minimum MTU supported by the interface (`IFLA_MIN_MTU`).
+    pub fn min_mtu(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_MIN_MTU)
+    }
+
+    /// Maximum MTU supported by the interface (`IFLA_MAX_MTU`).
+    pub fn max_mtu(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_MAX_MTU)
+    }
+
+    fn attribute_i32(&self, kind: u16) -> Option<i32> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::I32(value) if value.kind == kind => Some(value.value),
+                _ => None,
+            })
+    }
+
+    /// Interface index in the namespace the interface was moved to
+    /// (`IFLA_NEW_IFINDEX`).
+    pub fn new_index(&self) -> Option<i32> {
+        self.attribute_i32(libc::IFLA_NEW_IFINDEX)
+    }
+
+    /// Namespace ID the interface was moved to (`IFLA_NEW_NETNSID`).
+    pub fn new_netnsid(&self) -> Option<i32> {
+        self.attribute_i32(libc::IFLA_NEW_NETNSID)
+    }
+
+    /// Transmit queue length in packets (`IFLA_TXQLEN`).
+    pub fn txqlen(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_TXQLEN)
+    }
+
+    /// Interface group (`IFLA_GROUP`), zero is the default group.
+    pub fn group(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_GROUP)
+    }
+
+    /// Operational state (`IFLA_OPERSTATE`), `libc::IF_OPER_*` values.
+    pub fn operstate(&self) -> Option<u8> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U8(state) if state.kind == libc::IFLA_OPERSTATE => Some(state.value),
+                _ => None,
+            })
+    }
+
+    /// Number of SR-IOV virtual functions (`IFLA_NUM_VF`).
+    pub fn num_vf(&self) -> Option<u32> {
+        self.attribute_u32(libc::IFLA_NUM_VF)
+    }
+
+    fn attribute_string(&self, kind: u16) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::String(value) if value.kind == kind => Some(value.value.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Interface index (`ifi_index`).
+    pub fn index(&self) -> i32 {
+        self.message.index
+    }
+
+    /// Address family (`ifi_family`), see [`family`] constants.
+    pub fn family(&self) -> u8 {
+        self.message.family
+    }
+
+    /// Hardware type (`ifi_type`), e.g. `libc::ARPHRD_ETHER`.
+    pub fn link_type(&self) -> u16 {
+        self.message.kind
+    }
+
+    /// Hardware type (`ifi_type`) interpreted, see
+    /// [`Link::link_type`] for the raw value.
+    pub fn hardware_type(&self) -> ArpHardwareType {
+        ArpHardwareType::from(self.message.kind)
+    }
+
+    /// Interface name (`IFLA_IFNAME`).
+    pub fn name(&self) -> Option<&str> {
+        self.attribute_string(libc::IFLA_IFNAME)
+    }
+
+    fn attribute_mac(&self, kind: u16) -> Option<Mac> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Mac(address) if address.kind == kind => Some(address.value),
+                _ => None,
+            })
+    }
+
+    /// Interface hardware address (`IFLA_ADDRESS`).
+    pub fn address(&self) -> Option<Mac> {
+        self.attribute_mac(libc::IFLA_ADDRESS)
+    }
+
+    /// Permanent (burned-in) hardware address (`IFLA_PERM_ADDRESS`), the
+    /// current one may have been changed.
+    pub fn permanent_address(&self) -> Option<Mac> {
+        self.attribute_mac(libc::IFLA_PERM_ADDRESS)
+    }
+
+    /// Interface alias (`IFLA_IFALIAS`).
+    pub fn alias(&self) -> Option<&str> {
+        self.attribute_string(libc::IFLA_IFALIAS)
+    }
+
+    /// Interface alternative names (`IFLA_ALT_IFNAME` entries of
+    /// `IFLA_PROP_LIST`).
+    pub fn alt_names(&self) -> Vec<String> {
+        let mut names = vec![];
+        for attribute in &self.attributes {
+            let Attribute::Nested(list) = attribute else {
+                continue;
+            };
+            if list.kind & libc::NLA_TYPE_MASK as u16 != libc::IFLA_PROP_LIST {
+                continue;
+            }
+
+            let _ = visit_attributes(&list.value, |kind, payload| {
+                if kind == libc::IFLA_ALT_IFNAME {
+                    let end = payload
+                        .iter()
+                        .position(|&c| c == 0)
+                        .unwrap_or(payload.len());
+                    names.push(String::from_utf8_lossy(&payload[..end]).into_owned());
+                }
+            });
+        }
+        names
+    }
+
+    /// SR-IOV virtual functions information (`IFLA_VFINFO_LIST`).
+    pub fn vf_info(&self) -> Option<&[VfInfo]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::VfInfoList(list) => Some(list.value.vfs.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Legacy device hardware parameters (`IFLA_MAP`).
+    pub fn map(&self) -> Option<&IfMap> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::IfMap(map) => Some(&map.value),
+                _ => None,
+            })
+    }
+
+    /// Payload of the `IFLA_LINKINFO` nested attribute `kind`.
+    pub fn link_info(&self, kind: u16) -> Option<&[u8]> {
+        let info = self
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Nested(info) if info.kind == libc::IFLA_LINKINFO => {
+                    Some(info.value.as_slice())
+                }
+                _ => None,
+            })?;
+
+        let mut parser = PacketParser::new(info);
+        while parser.remaining() > 0 {
+            let (nested_kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            if nested_kind == kind {
+                return Some(payload);
+            }
+        }
+        None
+    }
+
+    fn link_info_string(&self, kind: u16) -> Option<&str> {
+        let payload = self.link_info(kind)?;
+        let end = payload
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(payload.len());
+        std::str::from_utf8(&payload[..end]).ok()
+    }
+
+    /// Interface type (`IFLA_INFO_KIND`), e.g. "bridge" or "vlan".
+    pub fn info_kind(&self) -> Option<&str> {
+        self.link_info_string(libc::IFLA_INFO_KIND)
+    }
+
+    /// Type of the master interface this interface is a port of
+    /// (`IFLA_INFO_SLAVE_KIND`), e.g. "bridge" or "bond".
+    pub fn slave_kind(&self) -> Option<&str> {
+        self.link_info_string(libc::IFLA_INFO_SLAVE_KIND)
+    }
+
+    /// Port attributes specific to the master type
+    /// (`IFLA_INFO_SLAVE_DATA`) in wire format.
+    pub fn slave_data(&self) -> Option<&[u8]> {
+        self.link_info(libc::IFLA_INFO_SLAVE_DATA)
+    }
+
+    /// VLAN information of "vlan" interfaces.
+    pub fn vlan_info(&self) -> Option<VlanInfo> {
+        if self.info_kind() != Some("vlan") {
+            return None;
+        }
+
+        let mut id = None;
+        let mut info = VlanInfo {
+            id: 0,
+            protocol: libc::ETH_P_8021Q as u16,
+            flags: 0,
+        };
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            match kind {
+                vlan_attribute::ID if payload.len() >= 2 => {
+                    id = Some(u16::from_ne_bytes([payload[0], payload[1]]))
+                }
+                // Network byte order.
+                vlan_attribute::PROTOCOL if payload.len() >= 2 => {
+                    info.protocol = u16::from_be_bytes([payload[0], payload[1]])
+                }
+                // `struct ifla_vlan_flags { __u32 flags; __u32 mask; }`
+                vlan_attribute::FLAGS if payload.len() >= 8 => {
+                    info.flags = PacketParser::new(payload).read_u32()
+                }
+                _ => (),
+            }
+        }
+
+        info.id = id?;
+        Some(info)
+    }
+
+    /// Bond information of "bond" interfaces.
+    pub fn bond_info(&self) -> Option<BondInfo> {
+        if self.info_kind() != Some("bond") {
+            return None;
+        }
+
+        let mut info = BondInfo {
+            mode: bond_mode::BALANCE_RR,
+            active_slave: None,
+            miimon: 0,
+        };
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            match kind {
+                bond_attribute::MODE if !payload.is_empty() => info.mode = payload[0],
+                bond_attribute::ACTIVE_SLAVE if payload.len() >= 4 => {
+                    info.active_slave = Some(PacketParser::new(payload).read_u32())
+                }
+                bond_attribute::MIIMON if payload.len() >= 4 => {
+                    info.miimon = PacketParser::new(payload).read_u32()
+                }
+                _ => (),
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Bridge information of "bridge" interfaces.
+    pub fn bridge_info(&self) -> Option<BridgeInfo> {
+        if self.info_kind() != Some("bridge") {
+            return None;
+        }
+
+        let mut info = BridgeInfo {
+            stp_state: 0,
+            forward_delay: 0,
+            priority: None,
+            vlan_filtering: false,
+        };
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            match kind {
+                bridge_attribute::STP_STATE if payload.len() >= 4 => {
+                    info.stp_state = PacketParser::new(payload).read_u32()
+                }
+                bridge_attribute::FORWARD_DELAY if payload.len() >= 4 => {
+                    info.forward_delay = PacketParser::new(payload).read_u32()
+                }
+                bridge_attribute::PRIORITY if payload.len() >= 2 => {
+                    info.priority = Some(PacketParser::new(payload).read_u16())
+                }
+                bridge_attribute::VLAN_FILTERING if !payload.is_empty() => {
+                    info.vlan_filtering = payload[0] != 0
+                }
+                _ => (),
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Peer name of veth interface requests (see
+    /// [`crate::message::route_builder::LinkBuilder::create_veth`]), the
+    /// kernel doesn't report it.
+    pub(crate) fn veth_peer_name(&self) -> Option<&str> {
+        if self.info_kind() != Some("veth") {
+            return None;
+        }
+
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            if kind != veth_attribute::PEER {
+                continue;
+            }
+
+            // Attributes follow the peer `struct ifinfomsg`.
+            let mut peer = PacketParser::new(payload.get(std::mem::size_of::<LinkMessage>()..)?);
+            while peer.remaining() > 0 {
+                let (kind, payload) = read_nested_attribute(&mut peer).ok()?;
+                if kind == libc::IFLA_IFNAME {
+                    let name = payload.strip_suffix(&[0]).unwrap_or(payload);
+                    return std::str::from_utf8(name).ok();
+                }
+            }
+        }
+        None
+    }
+
+    /// Tunnel information of GRE and IP tunnel interfaces.
+    pub fn tunnel_info(&self) -> Option<TunnelInfo> {
+        let (local, remote, ttl) = match self.info_kind()? {
+            "gre" | "gretap" | "ip6gre" | "ip6gretap" => (
+                gre_attribute::LOCAL,
+                gre_attribute::REMOTE,
+                gre_attribute::TTL,
+            ),
+            "ipip" | "sit" | "ip6tnl" => (
+                iptun_attribute::LOCAL,
+                iptun_attribute::REMOTE,
+                iptun_attribute::TTL,
+            ),
+            _ => return None,
+        };
+
+        let mut info = TunnelInfo {
+            local: None,
+            remote: None,
+            ttl: 0,
+            input_key: 0,
+            output_key: 0,
+        };
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            match kind {
+                _ if kind == local => info.local = tunnel_address(payload),
+                _ if kind == remote => info.remote = tunnel_address(payload),
+                _ if kind == ttl && !payload.is_empty() => info.ttl = payload[0],
+                // Keys only exist for GRE, in network byte order.
+                gre_attribute::IKEY if local == gre_attribute::LOCAL && payload.len() >= 4 => {
+                    info.input_key =
+                        u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]])
+                }
+                gre_attribute::OKEY if local == gre_attribute::LOCAL && payload.len() >= 4 => {
+                    info.output_key =
+                        u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]])
+                }
+                _ => (),
+            }
+        }
+
+        Some(info)
+    }
+
+    /// TUN/TAP information of "tun" interfaces.
+    pub fn tun_info(&self) -> Option<TunInfo> {
+        if self.info_kind() != Some("tun") {
+            return None;
+        }
+
+        let mut tun_type = None;
+        let mut info = TunInfo {
+            owner: None,
+            group: None,
+            tun_type: 0,
+        };
+        let mut parser = PacketParser::new(self.link_info(libc::IFLA_INFO_DATA)?);
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut parser).ok()?;
+            match kind {
+                tun_attribute::OWNER if payload.len() >= 4 => {
+                    info.owner = Some(PacketParser::new(payload).read_u32())
+                }
+                tun_attribute::GROUP if payload.len() >= 4 => {
+                    info.group = Some(PacketParser::new(payload).read_u32())
+                }
+                tun_attribute::TYPE if !payload.is_empty() => tun_type = Some(payload[0]),
+                _ => (),
+            }
+        }
+
+        info.tun_type = tun_type?;
+        Some(info)
+    }
+
+    /// IPv4 configuration (`IFLA_INET_CONF` of the `AF_INET` entry in
+    /// `IFLA_AF_SPEC`).
+    pub fn inet_conf(&self) -> Option<InetConf> {
+        let spec = self
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Nested(spec) if spec.kind == libc::IFLA_AF_SPEC => {
+                    Some(spec.value.as_slice())
+                }
+                _ => None,
+            })?;
+
+        let mut inet = None;
+        visit_attributes(spec, |family, payload| {
+            if family == libc::AF_INET as u16 {
+                inet = Some(payload);
+            }
+        })
+        .ok()?;
+
+        let mut conf = None;
+        visit_attributes(inet?, |kind, payload| {
+            if kind == inet_attribute::CONF {
+                conf = Some(payload);
+            }
         })
+        .ok()?;
+
+        let mut parser = PacketParser::new(conf?);
+        let values = (0..parser.remaining() / 4)
+            .map(|_| parser.read_i32())
+            .collect();
+        Some(InetConf(values))
+    }
+
+    /// Wireless extensions events (`IFLA_WIRELESS`) payloads, these are
+    /// not decoded.
+    pub fn wireless_events(&self) -> Vec<&[u8]> {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| match attribute {
+                Attribute::Wireless(event) => Some(event.value.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// All attributes of type `kind` in message order, for attributes
+    /// that may repeat. Alternative names (`IFLA_ALT_IFNAME`) only repeat
+    /// inside `IFLA_PROP_LIST`, see [`Link::alt_names`].
+    pub fn collect_attributes(&self, kind: u16) -> Vec<&Attribute> {
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.kind() == kind)
+            .collect()
     }
 
     pub fn to_array(self, writter: &mut PacketWriter) {
         writter.write_u8(self.message.family);
+        writter.write_u8(0);
         writter.write_u16(self.message.kind);
         writter.write_i32(self.message.index);
         writter.write_u32(self.message.flags);
         writter.write_u32(self.message.change);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
     }
 }
 
+/// Copy of the `attributes` with types in `kinds`, used to identify an
+/// object in delete requests.
+fn key_attributes(attributes: &[Attribute], kinds: &[u16]) -> Vec<Attribute> {
+    attributes
+        .iter()
+        .filter(|attribute| kinds.contains(&attribute.kind()))
+        .cloned()
+        .collect()
+}
+
 #[repr(C)]
 pub struct AddressMessage {
     /// See [`family`] constants.
@@ -154,19 +1095,2210 @@ pub struct AddressMessage {
     pub index: u32,
 }
 
-#[repr(C)]
-pub struct RouteMessage {
-    /// See [`family`] constants.
-    pub family: u8,
-    pub destination_prefix_length: u8,
-    pub source_prefix_length: u8,
-    pub type_of_service: u8,
-    pub table: u8,
-    /// See [`protocol`] constants.
-    pub protocol: u8,
-    /// See [`scope`] constants.
-    pub scope: u8,
-    pub kind: u8,
-    /// See [`route_flags`] for available flags.
-    pub flags: u32,
+// `struct ifaddrmsg` layout
+// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_addr.h)).
+const _: () = assert!(std::mem::size_of::<AddressMessage>() == 8);
+const _: () = assert!(std::mem::offset_of!(AddressMessage, index) == 4);
+
+/// Address scope (`ifa_scope`), see [`scope`] constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressScope {
+    Global,
+    Site,
+    Link,
+    Host,
+    Nowhere,
+    Other(u8),
+}
+
+impl From<u8> for AddressScope {
+    fn from(value: u8) -> AddressScope {
+        match value {
+            scope::UNIVERSE => AddressScope::Global,
+            scope::SITE => AddressScope::Site,
+            scope::LINK => AddressScope::Link,
+            scope::HOST => AddressScope::Host,
+            scope::NOWHERE => AddressScope::Nowhere,
+            _ => AddressScope::Other(value),
+        }
+    }
+}
+
+impl fmt::Display for AddressScope {
+    /// Scope names as printed by `ip address`.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AddressScope::Global => "global",
+            AddressScope::Site => "site",
+            AddressScope::Link => "link",
+            AddressScope::Host => "host",
+            AddressScope::Nowhere => "nowhere",
+            AddressScope::Other(value) => return write!(formatter, "{}", value),
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// Interface address (`RTM_NEWADDR`).
+pub struct Address {
+    pub message: AddressMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+impl Address {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<Address> {
+        if (parser.remaining() as usize) < std::mem::size_of::<AddressMessage>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let message = AddressMessage {
+            family: parser.read_u8(),
+            prefix_length: parser.read_u8(),
+            flags: parser.read_u8(),
+            scope: parser.read_u8(),
+            index: parser.read_u32(),
+        };
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                libc::IFA_ADDRESS | libc::IFA_LOCAL | libc::IFA_BROADCAST
+                    if message.family == family::INET =>
+                {
+                    attributes.push(Attribute::IPv4(AttributeValue::<NetIpv4>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                libc::IFA_ADDRESS | libc::IFA_LOCAL if message.family == family::INET6 => {
+                    attributes.push(Attribute::IPv6(AttributeValue::<NetIpv6>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                libc::IFA_LABEL => attributes.push(Attribute::String(
+                    AttributeValue::<String>::from(parser, length, kind)?,
+                )),
+                address_attribute::FLAGS => attributes.push(Attribute::U32(
+                    AttributeValue::<u32>::from(parser, length, kind)?,
+                )),
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(Address {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    fn attribute_address(&self, kind: u16) -> Option<IpAddr> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::IPv4(address) if address.kind == kind => {
+                    Some(IpAddr::V4(address.value.to_ipv4addr()))
+                }
+                Attribute::IPv6(address) if address.kind == kind => {
+                    Some(IpAddr::V6(address.value.to_ipv6addr()))
+                }
+                _ => None,
+            })
+    }
+
+    /// Interface address (`IFA_ADDRESS`), the peer address on point to
+    /// point interfaces.
+    pub fn address(&self) -> Option<IpAddr> {
+        self.attribute_address(libc::IFA_ADDRESS)
+    }
+
+    /// Local address (`IFA_LOCAL`).
+    pub fn local(&self) -> Option<IpAddr> {
+        self.attribute_address(libc::IFA_LOCAL)
+    }
+
+    /// Address label (`IFA_LABEL`).
+    pub fn label(&self) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::String(label) if label.kind == libc::IFA_LABEL => {
+                    Some(label.value.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    /// Address scope (`ifa_scope`).
+    pub fn scope(&self) -> AddressScope {
+        AddressScope::from(self.message.scope)
+    }
+
+    /// Request deleting this address (`RTM_DELADDR` with `NLM_F_ACK`),
+    /// keeping only the header and the attributes identifying it.
+    pub fn to_delete_request(&self, sequence: u32) -> NetlinkMessage<'static> {
+        let address = Address {
+            message: AddressMessage {
+                family: self.message.family,
+                prefix_length: self.message.prefix_length,
+                flags: 0,
+                scope: self.message.scope,
+                index: self.message.index,
+            },
+            attributes: key_attributes(&self.attributes, &[libc::IFA_ADDRESS, libc::IFA_LOCAL]),
+            unknown: vec![],
+        };
+        let length = std::mem::size_of::<NetlinkHeader>()
+            + std::mem::size_of::<AddressMessage>()
+            + address
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: libc::RTM_DELADDR,
+                flags: (libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16,
+                sequence,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Address(address)),
+        )
+    }
+
+    /// Address flags (`IFA_F_*`): `IFA_FLAGS` when present, otherwise the
+    /// 8 bits `ifa_flags` of the message header.
+    pub fn flags(&self) -> u32 {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U32(flags) if flags.kind == address_attribute::FLAGS => {
+                    Some(flags.value)
+                }
+                _ => None,
+            })
+            .unwrap_or(self.message.flags as u32)
+    }
+
+    /// Statically configured, never expires (`IFA_F_PERMANENT`).
+    pub fn is_permanent(&self) -> bool {
+        (self.flags() & libc::IFA_F_PERMANENT) != 0
+    }
+
+    /// IPv6 privacy extensions address (`IFA_F_TEMPORARY`).
+    pub fn is_temporary(&self) -> bool {
+        self.message.family == family::INET6 && (self.flags() & libc::IFA_F_TEMPORARY) != 0
+    }
+
+    /// Preferred lifetime expired (`IFA_F_DEPRECATED`).
+    pub fn is_deprecated(&self) -> bool {
+        (self.flags() & libc::IFA_F_DEPRECATED) != 0
+    }
+
+    /// Duplicate address detection still running (`IFA_F_TENTATIVE`).
+    pub fn is_tentative(&self) -> bool {
+        (self.flags() & libc::IFA_F_TENTATIVE) != 0
+    }
+
+    /// IPv4 address inside the subnet of another (primary) address
+    /// (`IFA_F_SECONDARY`).
+    pub fn is_secondary(&self) -> bool {
+        self.message.family == family::INET && (self.flags() & libc::IFA_F_SECONDARY) != 0
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_u8(self.message.prefix_length);
+        writter.write_u8(self.message.flags);
+        writter.write_u8(self.message.scope);
+        writter.write_u32(self.message.index);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+#[repr(C)]
+pub struct RouteMessage {
+    /// See [`family`] constants.
+    pub family: u8,
+    pub destination_prefix_length: u8,
+    pub source_prefix_length: u8,
+    pub type_of_service: u8,
+    pub table: u8,
+    /// See [`protocol`] constants.
+    pub protocol: u8,
+    /// See [`scope`] constants.
+    pub scope: u8,
+    pub kind: u8,
+    /// See [`route_flags`] for available flags.
+    pub flags: u32,
+}
+
+pub struct Route {
+    pub message: RouteMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+// `struct rtmsg` layout
+// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/rtnetlink.h)).
+const _: () = assert!(std::mem::size_of::<RouteMessage>() == 12);
+const _: () = assert!(std::mem::offset_of!(RouteMessage, flags) == 8);
+
+/// Route type (`rtm_type`), see [`route_type`] constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteType {
+    Unicast,
+    Local,
+    Broadcast,
+    Multicast,
+    Blackhole,
+    Unreachable,
+    Prohibit,
+    Throw,
+    Nat,
+    /// Other types (e.g. [`route_type::UNSPEC`] or [`route_type::ANYCAST`]).
+    Other(u8),
+}
+
+impl From<u8> for RouteType {
+    fn from(kind: u8) -> RouteType {
+        match kind {
+            route_type::UNICAST => RouteType::Unicast,
+            route_type::LOCAL => RouteType::Local,
+            route_type::BROADCAST => RouteType::Broadcast,
+            route_type::MULTICAST => RouteType::Multicast,
+            route_type::BLACKHOLE => RouteType::Blackhole,
+            route_type::UNREACHEABLE => RouteType::Unreachable,
+            route_type::PROHIBIT => RouteType::Prohibit,
+            route_type::THROW => RouteType::Throw,
+            route_type::NAT => RouteType::Nat,
+            _ => RouteType::Other(kind),
+        }
+    }
+}
+
+impl fmt::Display for RouteType {
+    /// Type names as printed by `ip route`.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RouteType::Unicast => "unicast",
+            RouteType::Local => "local",
+            RouteType::Broadcast => "broadcast",
+            RouteType::Multicast => "multicast",
+            RouteType::Blackhole => "blackhole",
+            RouteType::Unreachable => "unreachable",
+            RouteType::Prohibit => "prohibit",
+            RouteType::Throw => "throw",
+            RouteType::Nat => "nat",
+            RouteType::Other(kind) => return write!(formatter, "{}", kind),
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// IPv6 router preference (`RTA_PREF`,
+/// [RFC 4191](https://www.rfc-editor.org/rfc/rfc4191#section-2.1)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutePreference {
+    Low,
+    Medium,
+    High,
+    /// Reserved value (`ICMPV6_ROUTER_PREF_INVALID`).
+    Invalid,
+}
+
+impl From<u8> for RoutePreference {
+    fn from(preference: u8) -> RoutePreference {
+        // Two bits field: `01` high, `00` medium, `11` low and `10` invalid.
+        match preference & 0x3 {
+            0 => RoutePreference::Medium,
+            1 => RoutePreference::High,
+            3 => RoutePreference::Low,
+            _ => RoutePreference::Invalid,
+        }
+    }
+}
+
+/// Route realms (`RTA_FLOW`), used by the routing classifier and realm
+/// accounting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Realm {
+    /// Source realm (upper 16 bits).
+    pub from: u16,
+    /// Destination realm (lower 16 bits).
+    pub to: u16,
+}
+
+impl From<u32> for Realm {
+    fn from(flow: u32) -> Realm {
+        Realm {
+            from: (flow >> 16) as u16,
+            to: flow as u16,
+        }
+    }
+}
+
+/// Route light weight tunnel encapsulation (`RTA_ENCAP`).
+#[derive(Debug, PartialEq)]
+pub enum RouteEncap {
+    /// MPLS label stack pushed on the packets.
+    Mpls { labels: Vec<u32> },
+    /// Encapsulation without decoder: type and nested attributes in wire
+    /// format.
+    Other { kind: u16, data: Vec<u8> },
+}
+
+impl Route {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<Route> {
+        if (parser.remaining() as usize) < std::mem::size_of::<RouteMessage>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let message = RouteMessage {
+            family: parser.read_u8(),
+            destination_prefix_length: parser.read_u8(),
+            source_prefix_length: parser.read_u8(),
+            type_of_service: parser.read_u8(),
+            table: parser.read_u8(),
+            protocol: parser.read_u8(),
+            scope: parser.read_u8(),
+            kind: parser.read_u8(),
+            flags: parser.read_u32(),
+        };
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                route_attribute_type::PREF | route_attribute_type::TTL_PROPAGATE => attributes
+                    .push(Attribute::U8(AttributeValue::<u8>::from(
+                        parser, length, kind,
+                    )?)),
+                libc::RTA_OIF
+                | libc::RTA_IIF
+                | libc::RTA_FLOW
+                | libc::RTA_TABLE
+                | libc::RTA_MARK
+                | route_attribute_type::NH_ID => {
+                    attributes.push(Attribute::U32(AttributeValue::<u32>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                libc::RTA_DST | libc::RTA_SRC | libc::RTA_GATEWAY | libc::RTA_PREFSRC
+                    if message.family == family::INET =>
+                {
+                    attributes.push(Attribute::IPv4(AttributeValue::<NetIpv4>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                libc::RTA_DST | libc::RTA_SRC | libc::RTA_GATEWAY | libc::RTA_PREFSRC
+                    if message.family == family::INET6 =>
+                {
+                    attributes.push(Attribute::IPv6(AttributeValue::<NetIpv6>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                // Label stack entries kept in wire format, see
+                // `Route::mpls_labels`.
+                libc::RTA_DST | route_attribute_type::NEWDST if message.family == family::MPLS => {
+                    attributes.push(Attribute::MplsLabels(AttributeValue::<Vec<u8>>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                route_attribute_type::ENCAP_TYPE => attributes.push(Attribute::U16(
+                    AttributeValue::<u16>::from(parser, length, kind)?,
+                )),
+                route_attribute_type::ENCAP => attributes.push(Attribute::Nested(
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?,
+                )),
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(Route {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    /// Route type (`rtm_type`).
+    pub fn route_type(&self) -> RouteType {
+        RouteType::from(self.message.kind)
+    }
+
+    /// IPv6 route preference (`RTA_PREF`).
+    pub fn preference(&self) -> Option<RoutePreference> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U8(preference) if preference.kind == route_attribute_type::PREF => {
+                    Some(RoutePreference::from(preference.value))
+                }
+                _ => None,
+            })
+    }
+
+    /// Route realms (`RTA_FLOW`).
+    pub fn realm(&self) -> Option<Realm> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U32(flow) if flow.kind == libc::RTA_FLOW => {
+                    Some(Realm::from(flow.value))
+                }
+                _ => None,
+            })
+    }
+
+    fn attribute_u32(&self, kind: u16) -> Option<u32> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U32(value) if value.kind == kind => Some(value.value),
+                _ => None,
+            })
+    }
+
+    fn attribute_labels(&self, kind: u16) -> Option<Vec<u32>> {
+        if self.message.family != family::MPLS {
+            return None;
+        }
+
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::MplsLabels(stack) if stack.kind == kind => {
+                    Some(mpls_labels(&stack.value))
+                }
+                _ => None,
+            })
+    }
+
+    /// Incoming label stack (`RTA_DST`) of MPLS routes.
+    pub fn mpls_labels(&self) -> Option<Vec<u32>> {
+        self.attribute_labels(libc::RTA_DST)
+    }
+
+    /// Outgoing label stack (`RTA_NEWDST`) of MPLS routes, the labels
+    /// swapped in for [`Route::mpls_labels`].
+    pub fn mpls_new_labels(&self) -> Option<Vec<u32>> {
+        self.attribute_labels(route_attribute_type::NEWDST)
+    }
+
+    /// Whether MPLS routes copy the label TTL to the IP header when
+    /// popping the last label (`RTA_TTL_PROPAGATE`), `None` follows the
+    /// `net.mpls.ip_ttl_propagate` sysctl.
+    pub fn ttl_propagate(&self) -> Option<bool> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U8(value) if value.kind == route_attribute_type::TTL_PROPAGATE => {
+                    Some(value.value != 0)
+                }
+                _ => None,
+            })
+    }
+
+    /// Request deleting this route (`RTM_DELROUTE` with `NLM_F_ACK`),
+    /// keeping only the header and the attributes identifying it.
+    pub fn to_delete_request(&self, sequence: u32) -> NetlinkMessage<'static> {
+        let route = Route {
+            message: RouteMessage {
+                family: self.message.family,
+                destination_prefix_length: self.message.destination_prefix_length,
+                source_prefix_length: self.message.source_prefix_length,
+                type_of_service: self.message.type_of_service,
+                table: self.message.table,
+                protocol: self.message.protocol,
+                scope: self.message.scope,
+                kind: self.message.kind,
+                flags: 0,
+            },
+            attributes: key_attributes(
+                &self.attributes,
+                &[
+                    libc::RTA_DST,
+                    libc::RTA_SRC,
+                    libc::RTA_GATEWAY,
+                    libc::RTA_OIF,
+                    libc::RTA_PRIORITY,
+                    libc::RTA_TABLE,
+                    route_attribute_type::NH_ID,
+                ],
+            ),
+            unknown: vec![],
+        };
+        let length = std::mem::size_of::<NetlinkHeader>()
+            + std::mem::size_of::<RouteMessage>()
+            + route
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: libc::RTM_DELROUTE,
+                flags: (libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16,
+                sequence,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Route(route)),
+        )
+    }
+
+    /// Destination address (`RTA_DST`), absent for default routes.
+    pub fn destination(&self) -> Option<IpAddr> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::IPv4(address) if address.kind == libc::RTA_DST => {
+                    Some(IpAddr::V4(address.value.to_ipv4addr()))
+                }
+                Attribute::IPv6(address) if address.kind == libc::RTA_DST => {
+                    Some(IpAddr::V6(address.value.to_ipv6addr()))
+                }
+                _ => None,
+            })
+    }
+
+    /// Routing table: `RTA_TABLE` when present (tables above 255 only
+    /// fit there), otherwise `rtm_table`.
+    pub fn table(&self) -> u32 {
+        self.attribute_u32(libc::RTA_TABLE)
+            .unwrap_or(self.message.table as u32)
+    }
+
+    /// Output interface index (`RTA_OIF`).
+    pub fn oif(&self) -> Option<u32> {
+        self.attribute_u32(libc::RTA_OIF)
+    }
+
+    /// Input interface index (`RTA_IIF`), e.g. of policy routing lookups.
+    pub fn iif(&self) -> Option<u32> {
+        self.attribute_u32(libc::RTA_IIF)
+    }
+
+    /// Firewall mark (`RTA_MARK`), e.g. of policy routing lookups.
+    pub fn mark(&self) -> Option<u32> {
+        self.attribute_u32(libc::RTA_MARK)
+    }
+
+    /// Nexthop object used by the route (`RTA_NH_ID`), see
+    /// [`NextHopObject`].
+    pub fn nexthop_id(&self) -> Option<u32> {
+        self.attribute_u32(route_attribute_type::NH_ID)
+    }
+
+    /// Whether the route has nexthop information of its own (`RTA_GATEWAY`,
+    /// `RTA_OIF` or `RTA_MULTIPATH`) instead of a nexthop object.
+    pub fn has_inline_nexthop(&self) -> bool {
+        self.attributes.iter().any(|attribute| {
+            matches!(
+                attribute.kind(),
+                libc::RTA_GATEWAY | libc::RTA_OIF | libc::RTA_MULTIPATH
+            )
+        })
+    }
+
+    /// Light weight tunnel encapsulation (`RTA_ENCAP_TYPE` and
+    /// `RTA_ENCAP`).
+    pub fn encap(&self) -> Option<RouteEncap> {
+        let kind = self
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U16(kind) if kind.kind == route_attribute_type::ENCAP_TYPE => {
+                    Some(kind.value)
+                }
+                _ => None,
+            })?;
+        let data = self
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Nested(data) if data.kind == route_attribute_type::ENCAP => {
+                    Some(data.value.as_slice())
+                }
+                _ => None,
+            })?;
+
+        if kind == encap_type::MPLS {
+            let mut parser = PacketParser::new(data);
+            while parser.remaining() > 0 {
+                let (nested_kind, payload) = read_nested_attribute(&mut parser).ok()?;
+                if nested_kind == mpls_iptunnel::DST {
+                    return Some(RouteEncap::Mpls {
+                        labels: mpls_labels(payload),
+                    });
+                }
+            }
+        }
+
+        Some(RouteEncap::Other {
+            kind,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_u8(self.message.destination_prefix_length);
+        writter.write_u8(self.message.source_prefix_length);
+        writter.write_u8(self.message.type_of_service);
+        writter.write_u8(self.message.table);
+        writter.write_u8(self.message.protocol);
+        writter.write_u8(self.message.scope);
+        writter.write_u8(self.message.kind);
+        writter.write_u32(self.message.flags);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+#[repr(C)]
+pub struct NextHopMessage {
+    /// See [`family`] constants.
+    pub family: u8,
+    /// See [`scope`] constants.
+    pub scope: u8,
+    /// See [`protocol`] constants.
+    pub protocol: u8,
+    pub reserved: u8,
+    pub flags: u32,
+}
+
+/// Nexthop object (`RTM_NEWNEXTHOP`).
+pub struct NextHopObject {
+    pub message: NextHopMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+impl NextHopObject {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<NextHopObject> {
+        if (parser.remaining() as usize) < std::mem::size_of::<NextHopMessage>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let message = NextHopMessage {
+            family: parser.read_u8(),
+            scope: parser.read_u8(),
+            protocol: parser.read_u8(),
+            reserved: parser.read_u8(),
+            flags: parser.read_u32(),
+        };
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                nexthop_attribute::ID | nexthop_attribute::OIF => attributes.push(Attribute::U32(
+                    AttributeValue::<u32>::from(parser, length, kind)?,
+                )),
+                nexthop_attribute::GATEWAY if message.family == family::INET => attributes.push(
+                    Attribute::IPv4(AttributeValue::<NetIpv4>::from(parser, length, kind)?),
+                ),
+                nexthop_attribute::GATEWAY if message.family == family::INET6 => attributes.push(
+                    Attribute::IPv6(AttributeValue::<NetIpv6>::from(parser, length, kind)?),
+                ),
+                nexthop_attribute::GROUP => {
+                    attributes.push(Attribute::NextHopGroup(AttributeValue::<
+                        Vec<NextHopGroupMember>,
+                    >::from(
+                        parser, length, kind
+                    )?))
+                }
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(NextHopObject {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    fn attribute_u32(&self, kind: u16) -> Option<u32> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::U32(value) if value.kind == kind => Some(value.value),
+                _ => None,
+            })
+    }
+
+    /// Nexthop object identifier (`NHA_ID`).
+    pub fn id(&self) -> Option<u32> {
+        self.attribute_u32(nexthop_attribute::ID)
+    }
+
+    /// Output interface index (`NHA_OIF`).
+    pub fn oif(&self) -> Option<u32> {
+        self.attribute_u32(nexthop_attribute::OIF)
+    }
+
+    /// Gateway address (`NHA_GATEWAY`).
+    pub fn gateway(&self) -> Option<IpAddr> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::IPv4(address) if address.kind == nexthop_attribute::GATEWAY => {
+                    Some(IpAddr::V4(address.value.to_ipv4addr()))
+                }
+                Attribute::IPv6(address) if address.kind == nexthop_attribute::GATEWAY => {
+                    Some(IpAddr::V6(address.value.to_ipv6addr()))
+                }
+                _ => None,
+            })
+    }
+
+    /// Nexthop group members (`NHA_GROUP`).
+    pub fn group(&self) -> Option<&[NextHopGroupMember]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::NextHopGroup(group) => Some(group.value.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_u8(self.message.scope);
+        writter.write_u8(self.message.protocol);
+        writter.write_u8(self.message.reserved);
+        writter.write_u32(self.message.flags);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+/// Generic route message header (`struct rtgenmsg`), padded to 4 bytes on
+/// the wire.
+#[repr(C)]
+pub struct NsIdMessage {
+    /// See [`family`] constants.
+    pub family: u8,
+}
+
+/// Network namespace ID message (`RTM_NEWNSID`).
+pub struct NsId {
+    pub message: NsIdMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+impl NsId {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<NsId> {
+        if (parser.remaining() as usize) < 4 {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let message = NsIdMessage {
+            family: parser.read_u8(),
+        };
+        parser.align_to(4);
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                nsid_attribute::NSID
+                | nsid_attribute::FD
+                | nsid_attribute::TARGET_NSID
+                | nsid_attribute::CURRENT_NSID => attributes.push(Attribute::I32(
+                    AttributeValue::<i32>::from(parser, length, kind)?,
+                )),
+                nsid_attribute::PID => attributes.push(Attribute::U32(
+                    AttributeValue::<u32>::from(parser, length, kind)?,
+                )),
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(NsId {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    /// Namespace ID (`NETNSA_NSID`), [`NSID_NOT_ASSIGNED`] if none was
+    /// assigned.
+    pub fn nsid(&self) -> Option<i32> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::I32(value) if value.kind == nsid_attribute::NSID => Some(value.value),
+                _ => None,
+            })
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_bytes(&[0u8; 3]);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+/// Interface statistics message header (`struct if_stats_msg`).
+#[repr(C)]
+pub struct StatsMessage {
+    /// See [`family`] constants.
+    pub family: u8,
+    pub index: u32,
+    /// Requested attributes, see [`stats_attribute::filter_bit`].
+    pub filter_mask: u32,
+}
+
+/// Interface statistics message (`RTM_NEWSTATS`).
+pub struct LinkStatsExtended {
+    pub message: StatsMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+impl LinkStatsExtended {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<LinkStatsExtended> {
+        if (parser.remaining() as usize) < std::mem::size_of::<StatsMessage>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let family = parser.read_u8();
+        let _padding = parser.read_u8();
+        let _padding = parser.read_u16();
+        let message = StatsMessage {
+            family,
+            index: parser.read_u32(),
+            filter_mask: parser.read_u32(),
+        };
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                stats_attribute::LINK_64 => attributes.push(Attribute::LinkStats64(
+                    AttributeValue::<LinkStats64>::from(parser, length, kind)?,
+                )),
+                stats_attribute::LINK_XSTATS
+                | stats_attribute::LINK_XSTATS_SLAVE
+                | stats_attribute::LINK_OFFLOAD_XSTATS
+                | stats_attribute::AF_SPEC => {
+                    attributes.push(Attribute::Nested(AttributeValue::<Vec<u8>>::from(
+                        parser, length, kind,
+                    )?))
+                }
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(LinkStatsExtended {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    /// Interface counters (`IFLA_STATS_LINK_64`).
+    pub fn link_64(&self) -> Option<&LinkStats64> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::LinkStats64(stats) => Some(&stats.value),
+                _ => None,
+            })
+    }
+
+    /// Payload of the nested attribute `kind` (e.g.
+    /// [`stats_attribute::LINK_XSTATS`] with the driver specific
+    /// statistics), not decoded.
+    pub fn nested(&self, kind: u16) -> Option<&[u8]> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Nested(nested) if nested.kind == kind => Some(nested.value.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_bytes(&[0u8; 3]);
+        writter.write_u32(self.message.index);
+        writter.write_u32(self.message.filter_mask);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+/// Neighbor entry state (`ndm_state`), a set of `NUD_*` bits.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct NeighborState(pub u16);
+
+impl NeighborState {
+    const NAMES: [(u16, &'static str); 8] = [
+        (libc::NUD_INCOMPLETE, "INCOMPLETE"),
+        (libc::NUD_REACHABLE, "REACHABLE"),
+        (libc::NUD_STALE, "STALE"),
+        (libc::NUD_DELAY, "DELAY"),
+        (libc::NUD_PROBE, "PROBE"),
+        (libc::NUD_FAILED, "FAILED"),
+        (libc::NUD_NOARP, "NOARP"),
+        (libc::NUD_PERMANENT, "PERMANENT"),
+    ];
+
+    /// All the `state` bits (e.g. `libc::NUD_STALE`) are set.
+    pub fn contains(self, state: u16) -> bool {
+        self.0 & state == state
+    }
+
+    /// Recently confirmed (`NUD_REACHABLE`).
+    pub fn is_reachable(self) -> bool {
+        self.contains(libc::NUD_REACHABLE)
+    }
+
+    /// Not confirmed recently, still usable (`NUD_STALE`).
+    pub fn is_stale(self) -> bool {
+        self.contains(libc::NUD_STALE)
+    }
+
+    /// Static entry (`NUD_PERMANENT`).
+    pub fn is_permanent(self) -> bool {
+        self.contains(libc::NUD_PERMANENT)
+    }
+
+    /// Resolution failed (`NUD_FAILED`).
+    pub fn is_failed(self) -> bool {
+        self.contains(libc::NUD_FAILED)
+    }
+}
+
+impl fmt::Debug for NeighborState {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = NeighborState::NAMES
+            .iter()
+            .filter(|(state, _)| self.contains(*state))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        let known = NeighborState::NAMES
+            .iter()
+            .fold(0, |known, (state, _)| known | state);
+        let unknown = format!("{:#x}", self.0 & !known);
+        if self.0 & !known != 0 {
+            names.push(&unknown);
+        }
+        if names.is_empty() {
+            names.push("NONE");
+        }
+        write!(formatter, "NeighborState({})", names.join(" | "))
+    }
+}
+
+/// Neighbor message header (`struct ndmsg`).
+#[repr(C)]
+pub struct NeighborMessage {
+    /// See [`family`] constants.
+    pub family: u8,
+    pub index: i32,
+    /// `NUD_*` bits, see [`Neighbor::state`].
+    pub state: u16,
+    /// `NTF_*` flags.
+    pub flags: u8,
+    /// See [`route_type`] constants.
+    pub kind: u8,
+}
+
+/// Neighbor (ARP or NDP) entry message (`RTM_NEWNEIGH`).
+pub struct Neighbor {
+    pub message: NeighborMessage,
+    pub attributes: Vec<Attribute>,
+    /// Attributes without typed representation (type and payload) when
+    /// parsing with [`crate::message::ParseOptions::separate_unknown`].
+    pub unknown: Vec<(u16, Vec<u8>)>,
+}
+
+impl Neighbor {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<Neighbor> {
+        if (parser.remaining() as usize) < std::mem::size_of::<NeighborMessage>() {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let family = parser.read_u8();
+        let _padding = parser.read_u8();
+        let _padding = parser.read_u16();
+        let message = NeighborMessage {
+            family,
+            index: parser.read_i32(),
+            state: parser.read_u16(),
+            flags: parser.read_u8(),
+            kind: parser.read_u8(),
+        };
+        let mut attributes = vec![];
+        let mut unknown = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+
+            match kind {
+                libc::NDA_DST if message.family == family::INET => attributes.push(
+                    Attribute::IPv4(AttributeValue::<NetIpv4>::from(parser, length, kind)?),
+                ),
+                libc::NDA_DST if message.family == family::INET6 => attributes.push(
+                    Attribute::IPv6(AttributeValue::<NetIpv6>::from(parser, length, kind)?),
+                ),
+                // Other link layers (e.g. tunnels) use different sizes.
+                libc::NDA_LLADDR if length == 4 + 6 => attributes.push(Attribute::Mac(
+                    AttributeValue::<Mac>::from(parser, length, kind)?,
+                )),
+                libc::NDA_PROBES => attributes.push(Attribute::U32(AttributeValue::<u32>::from(
+                    parser, length, kind,
+                )?)),
+                _ if parser.options().separate_unknown => unknown.push((
+                    kind,
+                    AttributeValue::<Vec<u8>>::from(parser, length, kind)?.value,
+                )),
+                _ => attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                    parser, length, kind,
+                )?)),
+            }
+        }
+
+        Ok(Neighbor {
+            message,
+            attributes,
+            unknown,
+        })
+    }
+
+    /// Entry state, e.g. to filter the ARP table.
+    pub fn state(&self) -> NeighborState {
+        NeighborState(self.message.state)
+    }
+
+    /// Neighbor network address (`NDA_DST`).
+    pub fn destination(&self) -> Option<IpAddr> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::IPv4(address) if address.kind == libc::NDA_DST => {
+                    Some(IpAddr::V4(address.value.to_ipv4addr()))
+                }
+                Attribute::IPv6(address) if address.kind == libc::NDA_DST => {
+                    Some(IpAddr::V6(address.value.to_ipv6addr()))
+                }
+                _ => None,
+            })
+    }
+
+    /// Neighbor hardware address (`NDA_LLADDR`), Ethernet like link
+    /// layers only.
+    pub fn link_address(&self) -> Option<Mac> {
+        self.attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                Attribute::Mac(address) if address.kind == libc::NDA_LLADDR => Some(address.value),
+                _ => None,
+            })
+    }
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.message.family);
+        writter.write_bytes(&[0u8; 3]);
+        writter.write_i32(self.message.index);
+        writter.write_u16(self.message.state);
+        writter.write_u8(self.message.flags);
+        writter.write_u8(self.message.kind);
+        for attribute in &self.attributes {
+            attribute.to_array(writter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod route_test {
+    use super::*;
+    use crate::message::{
+        NetlinkMessage, NetlinkPayload, ParseOptions, NETLINK_MESSAGE_MAXIMUM_SIZE,
+    };
+
+    fn attribute(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let length = (ATTRIBUTE_HEADER_SIZE + payload.len()) as u16;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&length.to_ne_bytes());
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(payload);
+        bytes.resize(bytes.len().next_multiple_of(4), 0);
+        bytes
+    }
+
+    fn link_message(kind: u16, index: i32, attributes: &[u8]) -> Vec<u8> {
+        let length = (16 + 16 + attributes.len()) as u32;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&length.to_ne_bytes());
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Sequence
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        bytes.push(family::UNSPEC);
+        bytes.push(0); // Padding
+        bytes.extend_from_slice(&libc::ARPHRD_ETHER.to_ne_bytes());
+        bytes.extend_from_slice(&index.to_ne_bytes());
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Change
+        bytes.extend_from_slice(attributes);
+        bytes
+    }
+
+    fn parse_link(bytes: &[u8]) -> Link {
+        match NetlinkMessage::from(bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Link(link)),
+                ..
+            }) => link,
+            _ => unreachable!(),
+        }
+    }
+
+    fn route_message(
+        family: u8,
+        destination_length: u8,
+        protocol: u8,
+        attributes: &[u8],
+    ) -> Vec<u8> {
+        let length = (16 + 12 + attributes.len()) as u32;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&length.to_ne_bytes());
+        bytes.extend_from_slice(&libc::RTM_NEWROUTE.to_ne_bytes());
+        bytes.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Sequence
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        bytes.extend_from_slice(&[family, destination_length, 0, 0]);
+        bytes.extend_from_slice(&[
+            libc::RT_TABLE_MAIN,
+            protocol,
+            scope::UNIVERSE,
+            route_type::UNICAST,
+        ]);
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(attributes);
+        bytes
+    }
+
+    fn parse_route(bytes: &[u8]) -> Route {
+        match NetlinkMessage::from(bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Route(route)),
+                ..
+            }) => route,
+            _ => unreachable!(),
+        }
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn link_separate_unknown() {
+        let mut attributes = attribute(libc::IFLA_IFALIAS, b"uplink\0");
+        attributes.extend(attribute(1000, &[0x01, 0x02]));
+        attributes.extend(attribute(
+            libc::IFLA_ADDRESS,
+            &[0x02, 0x00, 0x00, 0x00, 0x00, 0x01],
+        ));
+        attributes.extend(attribute(1001, &[]));
+        let bytes = link_message(libc::RTM_NEWLINK, 2, &attributes);
+
+        let link = parse_link(&bytes);
+        assert_eq!(link.attributes.len(), 4);
+        assert!(link.unknown.is_empty());
+
+        let options = ParseOptions {
+            separate_unknown: true,
+            ..Default::default()
+        };
+        let link = match NetlinkMessage::from_with_options(&bytes, options) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Link(link)),
+                ..
+            }) => link,
+            _ => unreachable!(),
+        };
+        assert_eq!(link.attributes.len(), 2);
+        assert_eq!(link.alias(), Some("uplink"));
+        assert!(matches!(link.attributes[1], Attribute::Mac(_)));
+        assert_eq!(link.unknown, vec![(1000, vec![0x01, 0x02]), (1001, vec![])]);
+    }
+
+    #[test]
+    fn link_strict_unknown() {
+        let mut attributes = attribute(libc::IFLA_IFALIAS, b"uplink\0");
+        attributes.extend(attribute(1000, &[0x01, 0x02]));
+        let bytes = link_message(libc::RTM_NEWLINK, 2, &attributes);
+
+        // Lenient (default): kept as unknown attribute.
+        let link = parse_link(&bytes);
+        assert_eq!(link.alias(), Some("uplink"));
+        assert!(matches!(
+            &link.attributes[1],
+            Attribute::Unknown(unknown) if unknown.kind == 1000
+        ));
+
+        let options = ParseOptions {
+            strict_message_type: Some(libc::RTM_NEWLINK),
+            ..Default::default()
+        };
+        assert!(matches!(
+            NetlinkMessage::from_with_options(&bytes, options),
+            Err(NetlinkParseError::UnknownAttribute { kind: 1000 })
+        ));
+        let options = ParseOptions {
+            separate_unknown: true,
+            ..options
+        };
+        assert!(matches!(
+            NetlinkMessage::from_with_options(&bytes, options),
+            Err(NetlinkParseError::UnknownAttribute { kind: 1000 })
+        ));
+
+        // Other message types stay lenient.
+        let options = ParseOptions {
+            strict_message_type: Some(libc::RTM_NEWROUTE),
+            ..Default::default()
+        };
+        assert!(NetlinkMessage::from_with_options(&bytes, options).is_ok());
+
+        // Only typed attributes.
+        let bytes = link_message(
+            libc::RTM_NEWLINK,
+            2,
+            &attribute(libc::IFLA_IFALIAS, b"uplink\0"),
+        );
+        let options = ParseOptions {
+            strict_message_type: Some(libc::RTM_NEWLINK),
+            ..Default::default()
+        };
+        assert!(NetlinkMessage::from_with_options(&bytes, options).is_ok());
+    }
+
+    #[test]
+    fn route_mpls() {
+        // Labels 100 and 200 (bottom of stack), then label 300.
+        let mut attributes = attribute(
+            libc::RTA_DST,
+            &[0x00, 0x06, 0x40, 0x00, 0x00, 0x0c, 0x81, 0x00],
+        );
+        attributes.extend(attribute(
+            route_attribute_type::NEWDST,
+            &[0x00, 0x12, 0xc1, 0x00],
+        ));
+
+        let route = parse_route(&route_message(
+            family::MPLS,
+            20,
+            protocol::STATIC,
+            &attributes,
+        ));
+        assert_eq!(route.mpls_labels(), Some(vec![100, 200]));
+        assert_eq!(route.mpls_new_labels(), Some(vec![300]));
+        assert_eq!(route.destination(), None);
+
+        // Label stacks are typed attributes.
+        let options = ParseOptions {
+            strict_message_type: Some(libc::RTM_NEWROUTE),
+            ..Default::default()
+        };
+        let bytes = route_message(family::MPLS, 20, protocol::STATIC, &attributes);
+        assert!(NetlinkMessage::from_with_options(&bytes, options).is_ok());
+    }
+
+    #[test]
+    fn route_mpls_encap() {
+        let mut attributes = attribute(
+            route_attribute_type::ENCAP,
+            &attribute(mpls_iptunnel::DST, &[0x00, 0x06, 0x41, 0x00]),
+        );
+        attributes.extend(attribute(
+            route_attribute_type::ENCAP_TYPE,
+            &encap_type::MPLS.to_ne_bytes(),
+        ));
+
+        let route = parse_route(&route_message(
+            family::INET,
+            24,
+            protocol::STATIC,
+            &attributes,
+        ));
+        assert_eq!(route.message.destination_prefix_length, 24);
+        assert_eq!(route.message.protocol, protocol::STATIC);
+        assert_eq!(route.route_type(), RouteType::Unicast);
+        assert_eq!(route.encap(), Some(RouteEncap::Mpls { labels: vec![100] }));
+    }
+
+    #[test]
+    fn route_preference() {
+        let attributes = attribute(route_attribute_type::PREF, &[0]);
+
+        let route = parse_route(&route_message(family::INET6, 64, protocol::RA, &attributes));
+        assert_eq!(route.preference(), Some(RoutePreference::Medium));
+        assert_eq!(RoutePreference::from(1), RoutePreference::High);
+        assert_eq!(RoutePreference::from(3), RoutePreference::Low);
+    }
+
+    #[test]
+    fn route_realm() {
+        let attributes = attribute(libc::RTA_FLOW, &((1u32 << 16) | 2).to_ne_bytes());
+
+        let route = parse_route(&route_message(
+            family::INET,
+            24,
+            libc::RTPROT_STATIC,
+            &attributes,
+        ));
+        assert_eq!(route.realm(), Some(Realm { from: 1, to: 2 }));
+    }
+
+    #[test]
+    fn stats_reply() {
+        let stats = LinkStats64 {
+            rx_packets: 10,
+            tx_packets: 20,
+            rx_bytes: 1000,
+            tx_bytes: 2000,
+            rx_dropped: 1,
+            ..Default::default()
+        };
+        let mut counters = vec![];
+        for counter in [10u64, 20, 1000, 2000, 0, 0, 1] {
+            counters.extend_from_slice(&counter.to_ne_bytes());
+        }
+        counters.resize(25 * 8, 0);
+        let mut attributes = attribute(stats_attribute::LINK_64, &counters);
+        attributes.extend(attribute(
+            stats_attribute::LINK_XSTATS,
+            &attribute(1, &[0x01, 0x00, 0x00, 0x00]),
+        ));
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 12 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&message_type::NEWSTATS.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[family::UNSPEC, 0, 0, 0]);
+        bytes.extend_from_slice(&2u32.to_ne_bytes());
+        let filter_mask = stats_attribute::filter_bit(stats_attribute::LINK_64)
+            | stats_attribute::filter_bit(stats_attribute::LINK_XSTATS);
+        bytes.extend_from_slice(&filter_mask.to_ne_bytes());
+        bytes.extend_from_slice(&attributes);
+
+        match NetlinkMessage::from(&bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Stats(reply)),
+                ..
+            }) => {
+                assert_eq!(reply.message.index, 2);
+                assert_eq!(reply.message.filter_mask, 0b11);
+                assert_eq!(reply.link_64(), Some(&stats));
+                assert_eq!(
+                    reply.nested(stats_attribute::LINK_XSTATS),
+                    Some(&attribute(1, &[0x01, 0x00, 0x00, 0x00])[..])
+                );
+                assert_eq!(reply.nested(stats_attribute::AF_SPEC), None);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn neighbor_message(state: u16, attributes: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 12 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&libc::RTM_NEWNEIGH.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[family::INET, 0, 0, 0]);
+        bytes.extend_from_slice(&2i32.to_ne_bytes());
+        bytes.extend_from_slice(&state.to_ne_bytes());
+        bytes.extend_from_slice(&[0, route_type::UNICAST]);
+        bytes.extend_from_slice(attributes);
+        bytes
+    }
+
+    fn parse_neighbor(bytes: &[u8]) -> Neighbor {
+        match NetlinkMessage::from(bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Neighbor(neighbor)),
+                ..
+            }) => neighbor,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn neighbor_reachable() {
+        let mac = [0x02, 0x00, 0x00, 0x00, 0x00, 0x05];
+        let mut attributes = attribute(libc::NDA_DST, &[192, 0, 2, 5]);
+        attributes.extend(attribute(libc::NDA_LLADDR, &mac));
+        let neighbor = parse_neighbor(&neighbor_message(libc::NUD_REACHABLE, &attributes));
+
+        assert_eq!(neighbor.message.index, 2);
+        assert_eq!(neighbor.destination(), Some(IpAddr::from([192, 0, 2, 5])));
+        assert_eq!(neighbor.link_address(), Some(mac));
+        let state = neighbor.state();
+        assert!(state.is_reachable());
+        assert!(!state.is_permanent());
+        assert!(!state.is_failed());
+        assert!(!state.is_stale());
+        assert_eq!(format!("{:?}", state), "NeighborState(REACHABLE)");
+    }
+
+    #[test]
+    fn neighbor_permanent() {
+        let neighbor = parse_neighbor(&neighbor_message(
+            libc::NUD_PERMANENT,
+            &attribute(libc::NDA_DST, &[192, 0, 2, 6]),
+        ));
+        let state = neighbor.state();
+        assert!(state.is_permanent());
+        assert!(!state.is_reachable());
+        assert_eq!(format!("{:?}", state), "NeighborState(PERMANENT)");
+
+        assert_eq!(
+            format!(
+                "{:?}",
+                NeighborState(libc::NUD_STALE | libc::NUD_FAILED | 0x100)
+            ),
+            "NeighborState(STALE | FAILED | 0x100)"
+        );
+        assert_eq!(format!("{:?}", NeighborState(0)), "NeighborState(NONE)");
+    }
+
+    #[test]
+    fn route_mark() {
+        let attributes = attribute(libc::RTA_MARK, &0x10u32.to_ne_bytes());
+
+        let route = parse_route(&route_message(
+            family::INET,
+            0,
+            libc::RTPROT_BOOT,
+            &attributes,
+        ));
+        assert_eq!(route.mark(), Some(0x10));
+    }
+
+    #[test]
+    fn route_oif() {
+        let mut attributes = attribute(libc::RTA_OIF, &1u32.to_ne_bytes());
+        attributes.extend(attribute(libc::RTA_IIF, &7u32.to_ne_bytes()));
+
+        let route = parse_route(&route_message(
+            family::INET,
+            8,
+            libc::RTPROT_KERNEL,
+            &attributes,
+        ));
+        assert_eq!(route.oif(), Some(1));
+        assert_eq!(route.iif(), Some(7));
+        assert_eq!(route.mark(), None);
+    }
+
+    #[test]
+    fn route_nexthop_id() {
+        let attributes = attribute(route_attribute_type::NH_ID, &10u32.to_ne_bytes());
+
+        let route = parse_route(&route_message(
+            family::INET,
+            24,
+            libc::RTPROT_STATIC,
+            &attributes,
+        ));
+        assert_eq!(route.nexthop_id(), Some(10));
+        assert!(!route.has_inline_nexthop());
+    }
+
+    #[test]
+    fn address_flags() {
+        let mut attributes = attribute(
+            libc::IFA_ADDRESS,
+            &[
+                0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+            ],
+        );
+        attributes.extend(attribute(
+            address_attribute::FLAGS,
+            &(libc::IFA_F_TEMPORARY | libc::IFA_F_DEPRECATED).to_ne_bytes(),
+        ));
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 8 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&libc::RTM_NEWADDR.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[family::INET6, 64, 0, scope::UNIVERSE]);
+        bytes.extend_from_slice(&2u32.to_ne_bytes());
+        bytes.extend_from_slice(&attributes);
+
+        match NetlinkMessage::from(&bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Address(address)),
+                ..
+            }) => {
+                assert_eq!(address.message.index, 2);
+                assert_eq!(
+                    address.address(),
+                    Some("2001:db8::10".parse::<IpAddr>().unwrap())
+                );
+                assert!(address.is_temporary());
+                assert!(address.is_deprecated());
+                assert!(!address.is_secondary());
+                assert!(!address.is_permanent());
+                assert!(!address.is_tentative());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn route_type() {
+        assert_eq!(RouteType::from(libc::RTN_BLACKHOLE), RouteType::Blackhole);
+        assert_eq!(RouteType::from(libc::RTN_UNICAST), RouteType::Unicast);
+        assert_eq!(
+            RouteType::from(libc::RTN_ANYCAST),
+            RouteType::Other(libc::RTN_ANYCAST)
+        );
+        assert_eq!(RouteType::Blackhole.to_string(), "blackhole");
+        assert_eq!(RouteType::Other(42).to_string(), "42");
+    }
+
+    #[test]
+    fn hardware_type() {
+        assert_eq!(
+            ArpHardwareType::from(libc::ARPHRD_ETHER),
+            ArpHardwareType::Ether
+        );
+        assert_eq!(
+            ArpHardwareType::from(libc::ARPHRD_LOOPBACK),
+            ArpHardwareType::Loopback
+        );
+        assert_eq!(
+            ArpHardwareType::from(libc::ARPHRD_IEEE80211),
+            ArpHardwareType::Other(libc::ARPHRD_IEEE80211)
+        );
+        assert_eq!(ArpHardwareType::Ether.to_string(), "ether");
+        assert_eq!(ArpHardwareType::Loopback.to_string(), "loopback");
+
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 1, &[]));
+        assert_eq!(link.hardware_type(), ArpHardwareType::Ether);
+    }
+
+    #[test]
+    fn link_new_index() {
+        let mut attributes = attribute(libc::IFLA_NEW_NETNSID, &3i32.to_ne_bytes());
+        attributes.extend(attribute(libc::IFLA_NEW_IFINDEX, &12i32.to_ne_bytes()));
+        let link = parse_link(&link_message(libc::RTM_DELLINK, 5, &attributes));
+
+        assert_eq!(link.message.index, 5);
+        assert_eq!(link.new_netnsid(), Some(3));
+        assert_eq!(link.new_index(), Some(12));
+    }
+
+    #[test]
+    fn link_bridge_port() {
+        // `IFLA_BRPORT_STATE` (1) forwarding (3).
+        let port = attribute(1, &[3]);
+        let mut info = attribute(libc::IFLA_INFO_SLAVE_KIND, b"bridge\0");
+        info.extend(attribute(libc::IFLA_INFO_SLAVE_DATA, &port));
+        let mut attributes = attribute(libc::IFLA_LINKINFO, &info);
+        attributes.extend(attribute(libc::IFLA_MTU, &1500u32.to_ne_bytes()));
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 6, &attributes));
+
+        assert_eq!(link.info_kind(), None);
+        assert_eq!(link.slave_kind(), Some("bridge"));
+        assert_eq!(link.slave_data(), Some(port.as_slice()));
+        assert_eq!(link.mtu(), Some(1500));
+    }
+
+    #[test]
+    fn link_vlan_info() {
+        let mut data = attribute(vlan_attribute::ID, &100u16.to_ne_bytes());
+        data.extend(attribute(vlan_attribute::FLAGS, &u32s(&[1, u32::MAX])));
+        data.extend(attribute(
+            vlan_attribute::PROTOCOL,
+            &(libc::ETH_P_8021AD as u16).to_be_bytes(),
+        ));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"vlan\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            7,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+
+        assert_eq!(link.info_kind(), Some("vlan"));
+        assert_eq!(
+            link.vlan_info(),
+            Some(VlanInfo {
+                id: 100,
+                protocol: libc::ETH_P_8021AD as u16,
+                flags: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn link_bond_info() {
+        let mut data = attribute(bond_attribute::MODE, &[bond_mode::ACTIVE_BACKUP]);
+        data.extend(attribute(bond_attribute::ACTIVE_SLAVE, &4u32.to_ne_bytes()));
+        data.extend(attribute(bond_attribute::MIIMON, &100u32.to_ne_bytes()));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"bond\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            8,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+
+        assert_eq!(
+            link.bond_info(),
+            Some(BondInfo {
+                mode: bond_mode::ACTIVE_BACKUP,
+                active_slave: Some(4),
+                miimon: 100,
+            })
+        );
+        assert!(link.vlan_info().is_none());
+
+        let info = attribute(libc::IFLA_INFO_KIND, b"bridge\0");
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            9,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+        assert!(link.bond_info().is_none());
+    }
+
+    #[test]
+    fn link_tunnel_info() {
+        let mut data = attribute(gre_attribute::IKEY, &42u32.to_be_bytes());
+        data.extend(attribute(gre_attribute::OKEY, &42u32.to_be_bytes()));
+        data.extend(attribute(gre_attribute::LOCAL, &[192, 0, 2, 1]));
+        data.extend(attribute(gre_attribute::REMOTE, &[198, 51, 100, 1]));
+        data.extend(attribute(gre_attribute::TTL, &[64]));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"gre\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            11,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+
+        assert_eq!(
+            link.tunnel_info(),
+            Some(TunnelInfo {
+                local: Some(IpAddr::from([192, 0, 2, 1])),
+                remote: Some(IpAddr::from([198, 51, 100, 1])),
+                ttl: 64,
+                input_key: 42,
+                output_key: 42,
+            })
+        );
+
+        // IPv6 endpoints, any local address.
+        let remote: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let mut data = attribute(iptun_attribute::LOCAL, &[0; 16]);
+        data.extend(attribute(iptun_attribute::REMOTE, &remote.octets()));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"ip6tnl\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            12,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+        let tunnel = link.tunnel_info().unwrap();
+        assert_eq!(tunnel.local, None);
+        assert_eq!(tunnel.remote, Some(IpAddr::V6(remote)));
+        assert!(link.tun_info().is_none());
+    }
+
+    #[test]
+    fn link_tun_info() {
+        let mut data = attribute(tun_attribute::OWNER, &1000u32.to_ne_bytes());
+        data.extend(attribute(tun_attribute::TYPE, &[libc::IFF_TAP as u8]));
+        data.extend(attribute(tun_attribute::PI, &[0]));
+        data.extend(attribute(tun_attribute::PERSIST, &[1]));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"tun\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            10,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+
+        assert_eq!(
+            link.tun_info(),
+            Some(TunInfo {
+                owner: Some(1000),
+                group: None,
+                tun_type: libc::IFF_TAP as u8,
+            })
+        );
+        assert!(link.bond_info().is_none());
+    }
+
+    #[test]
+    fn link_bridge_info() {
+        let mut data = attribute(bridge_attribute::FORWARD_DELAY, &1500u32.to_ne_bytes());
+        data.extend(attribute(bridge_attribute::STP_STATE, &1u32.to_ne_bytes()));
+        data.extend(attribute(
+            bridge_attribute::PRIORITY,
+            &32768u16.to_ne_bytes(),
+        ));
+        data.extend(attribute(bridge_attribute::VLAN_FILTERING, &[1]));
+        data.extend(attribute(
+            bridge_attribute::VLAN_PROTOCOL,
+            &0x8100u16.to_be_bytes(),
+        ));
+        let mut info = attribute(libc::IFLA_INFO_KIND, b"bridge\0");
+        info.extend(attribute(libc::IFLA_INFO_DATA, &data));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            11,
+            &attribute(libc::IFLA_LINKINFO, &info),
+        ));
+
+        assert_eq!(
+            link.bridge_info(),
+            Some(BridgeInfo {
+                stp_state: 1,
+                forward_delay: 1500,
+                priority: Some(32768),
+                vlan_filtering: true,
+            })
+        );
+        assert!(link.tun_info().is_none());
+    }
+
+    #[test]
+    fn link_inet_conf() {
+        // Forwarding enabled, strict reverse path filtering.
+        let mut values = vec![0u32; ipv4_devconf::LOG_MARTIANS];
+        values[ipv4_devconf::FORWARDING - 1] = 1;
+        values[ipv4_devconf::RP_FILTER - 1] = 1;
+        let inet = attribute(inet_attribute::CONF, &u32s(&values));
+        let mut spec = attribute(libc::AF_INET6 as u16, &attribute(1, &[0; 4]));
+        spec.extend(attribute(libc::AF_INET as u16, &inet));
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            2,
+            &attribute(libc::IFLA_AF_SPEC, &spec),
+        ));
+
+        let conf = link.inet_conf().unwrap();
+        assert_eq!(conf.get(ipv4_devconf::FORWARDING), Some(1));
+        assert_eq!(conf.get(ipv4_devconf::RP_FILTER), Some(1));
+        assert_eq!(conf.get(ipv4_devconf::PROXY_ARP), Some(0));
+        assert_eq!(conf.get(0), None);
+        assert_eq!(conf.get(ipv4_devconf::LOG_MARTIANS + 1), None);
+    }
+
+    #[test]
+    fn link_for_each_attribute() {
+        let mut attributes = attribute(libc::IFLA_IFNAME, b"eth0\0");
+        attributes.extend(attribute(libc::IFLA_MTU, &1500u32.to_ne_bytes()));
+        attributes.extend(attribute(
+            libc::IFLA_ADDRESS,
+            &[0x02, 0x00, 0x00, 0x00, 0x00, 0x01],
+        ));
+        attributes.extend(attribute(libc::IFLA_OPERSTATE, &[6]));
+        let bytes = link_message(libc::RTM_NEWLINK, 2, &attributes);
+
+        let mut count = 0;
+        let mut mtu = None;
+        Link::for_each_attribute(&bytes, |kind, payload| {
+            count += 1;
+            if kind == libc::IFLA_MTU {
+                mtu = Some(u32::from_ne_bytes(payload.try_into().unwrap()));
+            }
+        })
+        .unwrap();
+
+        assert_eq!(count, parse_link(&bytes).attributes.len());
+        assert_eq!(mtu, Some(1500));
+    }
+
+    #[test]
+    fn address_scope() {
+        assert_eq!(AddressScope::from(libc::RT_SCOPE_LINK), AddressScope::Link);
+        assert_eq!(AddressScope::from(libc::RT_SCOPE_HOST), AddressScope::Host);
+        assert_eq!(
+            AddressScope::from(libc::RT_SCOPE_UNIVERSE),
+            AddressScope::Global
+        );
+        assert_eq!(AddressScope::from(100), AddressScope::Other(100));
+        assert_eq!(AddressScope::Global.to_string(), "global");
+        assert_eq!(AddressScope::Link.to_string(), "link");
+        assert_eq!(AddressScope::Host.to_string(), "host");
+        assert_eq!(AddressScope::Other(100).to_string(), "100");
+    }
+
+    #[test]
+    fn address_delete_request() {
+        let mut attributes = attribute(libc::IFA_ADDRESS, &[192, 0, 2, 10]);
+        attributes.extend(attribute(libc::IFA_LOCAL, &[192, 0, 2, 10]));
+        attributes.extend(attribute(libc::IFA_LABEL, b"eth0\0\0\0\0"));
+        attributes.extend(attribute(
+            address_attribute::FLAGS,
+            &libc::IFA_F_PERMANENT.to_ne_bytes(),
+        ));
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 8 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&libc::RTM_NEWADDR.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[
+            family::INET,
+            24,
+            libc::IFA_F_PERMANENT as u8,
+            scope::UNIVERSE,
+        ]);
+        bytes.extend_from_slice(&2u32.to_ne_bytes());
+        bytes.extend_from_slice(&attributes);
+
+        let address = match NetlinkMessage::from(&bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Address(address)),
+                ..
+            }) => address,
+            _ => unreachable!(),
+        };
+
+        let request = address.to_delete_request(9);
+        assert!(request.validate().is_ok());
+        let mut buffer = [0u8; 64];
+        let length = request.to_array(&mut buffer);
+        let message = NetlinkMessage::from(&buffer[..length]).unwrap();
+        assert_eq!(message.header.kind, libc::RTM_DELADDR);
+        assert_eq!(
+            message.header.flags,
+            (libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16
+        );
+        assert_eq!(message.header.sequence, 9);
+        match message.payload {
+            NetlinkPayload::Route(MessageType::Address(deleted)) => {
+                assert_eq!(deleted.message.family, family::INET);
+                assert_eq!(deleted.message.prefix_length, 24);
+                assert_eq!(deleted.message.index, 2);
+                assert_eq!(deleted.address(), address.address());
+                assert_eq!(deleted.local(), address.local());
+                assert_eq!(deleted.label(), None);
+                assert_eq!(deleted.attributes.len(), 2);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn link_permanent_address() {
+        let current = [0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+        let permanent = [0x00, 0x1b, 0x21, 0x3a, 0x4b, 0x5c];
+        let mut attributes = attribute(libc::IFLA_ADDRESS, &current);
+        attributes.extend(attribute(libc::IFLA_PERM_ADDRESS, &permanent));
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 2, &attributes));
+
+        assert_eq!(link.address(), Some(current));
+        assert_eq!(link.permanent_address(), Some(permanent));
+    }
+
+    #[test]
+    fn link_collect_attributes() {
+        // Not sent flat by the kernel (see `alt_names`), but repeats are kept.
+        let alt_name = libc::IFLA_ALT_IFNAME;
+        let mut attributes = attribute(alt_name, b"first\0");
+        attributes.extend(attribute(libc::IFLA_MTU, &1500u32.to_ne_bytes()));
+        attributes.extend(attribute(alt_name, b"second\0"));
+        attributes.extend(attribute(alt_name, b"third\0"));
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 2, &attributes));
+
+        let names: Vec<&[u8]> = link
+            .collect_attributes(alt_name)
+            .into_iter()
+            .map(|attribute| match attribute {
+                Attribute::Unknown(name) => name.value.as_slice(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(names, vec![&b"first\0"[..], b"second\0", b"third\0"]);
+        assert_eq!(link.collect_attributes(libc::IFLA_MTU).len(), 1);
+        assert!(link.collect_attributes(libc::IFLA_IFALIAS).is_empty());
+    }
+
+    #[test]
+    fn link_alt_names() {
+        let mut names = attribute(libc::IFLA_ALT_IFNAME, b"uplink\0");
+        names.extend(attribute(libc::IFLA_ALT_IFNAME, b"wan0\0"));
+        let attributes = attribute(libc::IFLA_PROP_LIST | libc::NLA_F_NESTED as u16, &names);
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 2, &attributes));
+
+        assert_eq!(link.alt_names(), vec!["uplink", "wan0"]);
+        assert!(parse_link(&link_message(libc::RTM_NEWLINK, 2, &[]))
+            .alt_names()
+            .is_empty());
+    }
+
+    #[test]
+    fn link_group() {
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            2,
+            &attribute(libc::IFLA_GROUP, &5u32.to_ne_bytes()),
+        ));
+        assert_eq!(link.group(), Some(5));
+    }
+
+    #[test]
+    fn link_index() {
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            42,
+            &attribute(libc::IFLA_IFNAME, b"eth0\0"),
+        ));
+
+        assert_eq!(link.index(), 42);
+        assert_eq!(link.family(), family::UNSPEC);
+        assert_eq!(link.link_type(), libc::ARPHRD_ETHER);
+        assert_eq!(
+            format!("{:?}", link),
+            format!(
+                "Link {{ index: 42, family: 0, link_type: {}, flags: 0x0, name: Some(\"eth0\"), .. }}",
+                link.link_type()
+            )
+        );
+    }
+
+    #[test]
+    fn link_num_vf() {
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            3,
+            &attribute(libc::IFLA_NUM_VF, &8u32.to_ne_bytes()),
+        ));
+        assert_eq!(link.num_vf(), Some(8));
+        assert!(link.vf_info().is_none());
+    }
+
+    #[test]
+    fn link_mtu_range() {
+        let mut attributes = attribute(libc::IFLA_MTU, &1500u32.to_ne_bytes());
+        attributes.extend(attribute(libc::IFLA_MIN_MTU, &68u32.to_ne_bytes()));
+        attributes.extend(attribute(libc::IFLA_MAX_MTU, &65535u32.to_ne_bytes()));
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 2, &attributes));
+
+        assert_eq!(link.mtu(), Some(1500));
+        assert_eq!(link.min_mtu(), Some(68));
+        assert_eq!(link.max_mtu(), Some(65535));
+    }
+
+    #[test]
+    fn link_wireless_event() {
+        // `struct iw_event` with `SIOCGIWSCAN` and no payload.
+        let event = [0x08, 0x00, 0x19, 0x8b, 0x00, 0x00, 0x00, 0x00];
+        let mut attributes = attribute(libc::IFLA_IFNAME, b"wlan0\0");
+        attributes.extend(attribute(libc::IFLA_WIRELESS, &event));
+        let link = parse_link(&link_message(libc::RTM_NEWLINK, 4, &attributes));
+
+        assert_eq!(link.wireless_events(), vec![&event[..]]);
+        assert_eq!(link.name(), Some("wlan0"));
+    }
+
+    #[test]
+    fn link_map() {
+        // `IFLA_MAP` of an ISA NE2000 card at I/O 0x300 and IRQ 10.
+        let map = [
+            0x00, 0x00, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x00, // mem_start
+            0xff, 0x3f, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x00, // mem_end
+            0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // base_addr
+            0x0a, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // irq, dma, port
+        ];
+        let link = parse_link(&link_message(
+            libc::RTM_NEWLINK,
+            2,
+            &attribute(libc::IFLA_MAP, &map),
+        ));
+
+        let map = link.map().unwrap();
+        if cfg!(target_endian = "little") {
+            assert_eq!(map.mem_start, 0xd0000);
+            assert_eq!(map.mem_end, 0xd3fff);
+            assert_eq!(map.base_addr, 0x300);
+            assert_eq!(map.irq, 10);
+        }
+        assert_eq!(map.dma, 0);
+        assert_eq!(map.port, 1);
+    }
+
+    #[test]
+    fn nsid() {
+        let attributes = attribute(nsid_attribute::NSID, &7i32.to_ne_bytes());
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 4 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&libc::RTM_NEWNSID.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[family::UNSPEC, 0, 0, 0]);
+        bytes.extend_from_slice(&attributes);
+
+        match NetlinkMessage::from(&bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::NsId(nsid)),
+                ..
+            }) => assert_eq!(nsid.nsid(), Some(7)),
+            _ => unreachable!(),
+        }
+    }
+
+    fn vf_info(index: u32, mac: Mac, vlan: u32, max_tx_rate: u32) -> Vec<u8> {
+        let mut vf_mac = index.to_ne_bytes().to_vec();
+        vf_mac.extend_from_slice(&mac);
+        vf_mac.resize(4 + 32, 0);
+
+        let mut info = attribute(vf_attribute::MAC, &vf_mac);
+        info.extend(attribute(vf_attribute::VLAN, &u32s(&[index, vlan, 0])));
+        info.extend(attribute(
+            vf_attribute::RATE,
+            &u32s(&[index, 0, max_tx_rate]),
+        ));
+        info.extend(attribute(vf_attribute::SPOOFCHK, &u32s(&[index, 1])));
+        attribute(vf_attribute::INFO, &info)
+    }
+
+    #[test]
+    fn link_vf_info() {
+        let mut list = vf_info(0, [0x02, 0, 0, 0, 0, 0x10], 100, 1000);
+        list.extend(vf_info(1, [0x02, 0, 0, 0, 0, 0x11], 200, 0));
+        let bytes = link_message(
+            libc::RTM_NEWLINK,
+            3,
+            &attribute(libc::IFLA_VFINFO_LIST, &list),
+        );
+
+        let link = parse_link(&bytes);
+        assert_eq!(link.message.index, 3);
+        assert_eq!(link.message.kind, libc::ARPHRD_ETHER);
+
+        let vfs = link.vf_info().unwrap();
+        assert_eq!(vfs.len(), 2);
+        assert_eq!(vfs[0].index, 0);
+        assert_eq!(vfs[0].mac, Some([0x02, 0, 0, 0, 0, 0x10]));
+        assert_eq!(vfs[0].vlan, Some(100));
+        assert_eq!(vfs[0].max_tx_rate, Some(1000));
+        assert_eq!(vfs[0].spoof_check, Some(true));
+        assert_eq!(vfs[1].index, 1);
+        assert_eq!(vfs[1].mac, Some([0x02, 0, 0, 0, 0, 0x11]));
+        assert_eq!(vfs[1].vlan, Some(200));
+        assert_eq!(vfs[1].max_tx_rate, Some(0));
+
+        // Re-serialized with the whole list.
+        let mut serialized = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = NetlinkMessage::from(&bytes)
+            .unwrap()
+            .to_array(&mut serialized);
+        assert_eq!(&serialized[..length], bytes.as_slice());
+    }
+
+    #[test]
+    fn nexthop_gateway() {
+        let mut attributes = attribute(nexthop_attribute::ID, &10u32.to_ne_bytes());
+        attributes.extend(attribute(nexthop_attribute::GATEWAY, &[192, 0, 2, 1]));
+        attributes.extend(attribute(nexthop_attribute::OIF, &2u32.to_ne_bytes()));
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((16 + 8 + attributes.len()) as u32).to_ne_bytes());
+        bytes.extend_from_slice(&message_type::NEWNEXTHOP.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[family::INET, scope::UNIVERSE, protocol::STATIC, 0]);
+        bytes.extend_from_slice(&0u32.to_ne_bytes());
+        bytes.extend_from_slice(&attributes);
+
+        match NetlinkMessage::from(&bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::NextHop(nexthop)),
+                ..
+            }) => {
+                assert_eq!(nexthop.message.protocol, protocol::STATIC);
+                assert_eq!(nexthop.id(), Some(10));
+                assert_eq!(nexthop.gateway(), Some("192.0.2.1".parse().unwrap()));
+                assert_eq!(nexthop.oif(), Some(2));
+                assert!(nexthop.group().is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -21,47 +21,393 @@
 // OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
 // SUCH DAMAGE.
 
-use super::{packet_parser::PacketParser, NetlinkParseResult};
+use super::{
+    packet_parser::PacketParser, packet_writer::PacketWriter, NetlinkParseError, NetlinkParseResult,
+};
+use std::net::{Ipv4Addr, Ipv6Addr};
 
-pub type IPv4 = u32;
-pub type IPv6 = [u32; 4];
 pub type Mac = [u8; 6];
 
+/// IPv4 address as found on the wire (network byte order), convert with
+/// [`NetIpv4::to_ipv4addr`] instead of reading it as an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetIpv4(pub [u8; 4]);
+
+impl NetIpv4 {
+    pub fn from_ipv4addr(address: Ipv4Addr) -> NetIpv4 {
+        NetIpv4(address.octets())
+    }
+
+    pub fn to_ipv4addr(self) -> Ipv4Addr {
+        Ipv4Addr::from(self.0)
+    }
+}
+
+/// IPv6 address as found on the wire (network byte order), convert with
+/// [`NetIpv6::to_ipv6addr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetIpv6(pub [u8; 16]);
+
+impl NetIpv6 {
+    pub fn from_ipv6addr(address: Ipv6Addr) -> NetIpv6 {
+        NetIpv6(address.octets())
+    }
+
+    pub fn to_ipv6addr(self) -> Ipv6Addr {
+        Ipv6Addr::from(self.0)
+    }
+}
+
+/// Attribute header size (length and type).
+pub const ATTRIBUTE_HEADER_SIZE: usize = 4;
+
+/// Suggested [`parse_attribute_tree`] maximum nesting depth.
+pub const ATTRIBUTE_DEFAULT_NESTING_DEPTH: usize = 8;
+
+/// SR-IOV virtual function attribute types
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/uapi/linux/if_link.h#L971)).
+///
+/// `INFO` is the only type found inside `IFLA_VFINFO_LIST`, the other
+/// types are nested inside `INFO`.
+pub mod vf_attribute {
+    pub const INFO: u16 = 1;
+    pub const MAC: u16 = 1;
+    pub const VLAN: u16 = 2;
+    pub const TX_RATE: u16 = 3;
+    pub const SPOOFCHK: u16 = 4;
+    pub const LINK_STATE: u16 = 5;
+    pub const RATE: u16 = 6;
+}
+
+/// Read the payload of an attribute whose header was already consumed.
+///
+/// The declared `length` (header included) must fit at least
+/// `minimum_size` bytes of payload and the parser is moved past the
+/// attribute padding.
+pub fn read_payload<'a>(
+    parser: &mut PacketParser<'a>,
+    length: u16,
+    minimum_size: usize,
+) -> NetlinkParseResult<&'a [u8]> {
+    let length = length as usize;
+    if length < ATTRIBUTE_HEADER_SIZE + minimum_size {
+        return Err(NetlinkParseError::InvalidAttributeLength);
+    }
+    if (length - ATTRIBUTE_HEADER_SIZE) > (parser.remaining() as usize) {
+        return Err(NetlinkParseError::MessageIncomplete);
+    }
+
+    let payload = parser.read_slice(length - ATTRIBUTE_HEADER_SIZE);
+    parser.align_to(4);
+    Ok(payload)
+}
+
+/// Read the next attribute in `parser` and return its type and payload.
+///
+/// The parser is moved past the attribute padding.
+pub fn read_nested_attribute<'a>(
+    parser: &mut PacketParser<'a>,
+) -> NetlinkParseResult<(u16, &'a [u8])> {
+    if (parser.remaining() as usize) < ATTRIBUTE_HEADER_SIZE {
+        return Err(NetlinkParseError::MessageIncomplete);
+    }
+
+    let length = parser.read_u16();
+    let kind = parser.read_u16();
+    Ok((kind, read_payload(parser, length, 0)?))
+}
+
+/// Call `visitor` with the type and payload of every attribute in `bytes`,
+/// without allocating.
+pub fn visit_attributes<'a, F>(bytes: &'a [u8], mut visitor: F) -> NetlinkParseResult<()>
+where
+    F: FnMut(u16, &'a [u8]),
+{
+    let mut parser = PacketParser::new(bytes);
+    while parser.remaining() > 0 {
+        let (kind, payload) = read_nested_attribute(&mut parser)?;
+        visitor(kind, payload);
+    }
+    Ok(())
+}
+
+/// Attribute decoded by [`parse_attribute_tree`].
+#[derive(Debug, PartialEq)]
+pub enum AttributeNode<'a> {
+    /// Attribute without the nested flag: type and payload.
+    Value { kind: u16, payload: &'a [u8] },
+    /// Attribute with the nested flag (`NLA_F_NESTED`) and its decoded
+    /// children.
+    Nested {
+        kind: u16,
+        attributes: Vec<AttributeNode<'a>>,
+    },
+}
+
+/// Decode the attributes in `bytes` recursively, following attributes
+/// flagged with `NLA_F_NESTED` (types are reported without the flags).
+///
+/// Recursion stops at `max_depth` levels (e.g.
+/// [`ATTRIBUTE_DEFAULT_NESTING_DEPTH`]) with
+/// [`NetlinkParseError::NestingTooDeep`], so crafted messages can't
+/// exhaust the stack.
+pub fn parse_attribute_tree(
+    bytes: &[u8],
+    max_depth: usize,
+) -> NetlinkParseResult<Vec<AttributeNode<'_>>> {
+    parse_attribute_level(bytes, max_depth)
+}
+
+fn parse_attribute_level(bytes: &[u8], depth: usize) -> NetlinkParseResult<Vec<AttributeNode<'_>>> {
+    let mut attributes = vec![];
+    let mut parser = PacketParser::new(bytes);
+    while parser.remaining() > 0 {
+        let (kind, payload) = read_nested_attribute(&mut parser)?;
+        let flags = kind & !(libc::NLA_TYPE_MASK as u16);
+        let kind = kind & libc::NLA_TYPE_MASK as u16;
+        if flags & libc::NLA_F_NESTED as u16 == 0 {
+            attributes.push(AttributeNode::Value { kind, payload });
+            continue;
+        }
+
+        if depth == 0 {
+            return Err(NetlinkParseError::NestingTooDeep);
+        }
+        attributes.push(AttributeNode::Nested {
+            kind,
+            attributes: parse_attribute_level(payload, depth - 1)?,
+        });
+    }
+    Ok(attributes)
+}
+
+/// Serialize `attributes` to use as payload of a nested attribute.
+pub fn nested_payload(attributes: &[Attribute]) -> Vec<u8> {
+    let mut payload = vec![0u8; attributes.iter().map(Attribute::length).sum()];
+    let mut writer = PacketWriter::new(&mut payload);
+    for attribute in attributes {
+        attribute.to_array(&mut writer);
+    }
+    payload
+}
+
+/// Decode a MPLS label stack (network byte order label stack entries) into
+/// the list of labels, stopping at the bottom of stack entry.
+pub fn mpls_labels(bytes: &[u8]) -> Vec<u32> {
+    let mut labels = vec![];
+    for entry in bytes.chunks_exact(4) {
+        let entry = u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        labels.push(entry >> 12);
+        // Bottom of stack bit.
+        if (entry & 0x100) != 0 {
+            break;
+        }
+    }
+    labels
+}
+
+/// SR-IOV virtual function information.
+#[derive(Clone)]
+pub struct VfInfo {
+    /// Virtual function index.
+    pub index: u32,
+    pub mac: Option<Mac>,
+    pub vlan: Option<u32>,
+    pub qos: Option<u32>,
+    /// Minimum transmit rate in Mbps (zero means disabled).
+    pub min_tx_rate: Option<u32>,
+    /// Maximum transmit rate in Mbps (zero means disabled).
+    pub max_tx_rate: Option<u32>,
+    pub spoof_check: Option<bool>,
+}
+
+impl VfInfo {
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<VfInfo> {
+        let mut info = VfInfo {
+            index: 0,
+            mac: None,
+            vlan: None,
+            qos: None,
+            min_tx_rate: None,
+            max_tx_rate: None,
+            spoof_check: None,
+        };
+
+        while parser.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(parser)?;
+            let mut value = PacketParser::new(payload);
+
+            match kind {
+                // `struct ifla_vf_mac { __u32 vf; __u8 mac[32]; }`
+                vf_attribute::MAC if payload.len() >= 10 => {
+                    info.index = value.read_u32();
+                    info.mac = Some(value.read_mac());
+                }
+                // `struct ifla_vf_vlan { __u32 vf; __u32 vlan; __u32 qos; }`
+                vf_attribute::VLAN if payload.len() >= 12 => {
+                    info.index = value.read_u32();
+                    info.vlan = Some(value.read_u32());
+                    info.qos = Some(value.read_u32());
+                }
+                // `struct ifla_vf_rate { __u32 vf; __u32 min_tx_rate; __u32 max_tx_rate; }`
+                vf_attribute::RATE if payload.len() >= 12 => {
+                    info.index = value.read_u32();
+                    info.min_tx_rate = Some(value.read_u32());
+                    info.max_tx_rate = Some(value.read_u32());
+                }
+                // `struct ifla_vf_spoofchk { __u32 vf; __u32 setting; }`
+                vf_attribute::SPOOFCHK if payload.len() >= 8 => {
+                    info.index = value.read_u32();
+                    info.spoof_check = Some(value.read_u32() != 0);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(info)
+    }
+}
+
+/// Legacy device hardware parameters (`struct rtnl_link_ifmap` in
+/// `IFLA_MAP`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IfMap {
+    pub mem_start: u64,
+    pub mem_end: u64,
+    pub base_addr: u64,
+    pub irq: u16,
+    pub dma: u8,
+    pub port: u8,
+}
+
+/// Interface counters (`struct rtnl_link_stats64`).
+///
+/// Counters added by newer kernels (`rx_nohandler` and
+/// `rx_otherhost_dropped`) are zero when not reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkStats64 {
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub multicast: u64,
+    pub collisions: u64,
+    pub rx_length_errors: u64,
+    pub rx_over_errors: u64,
+    pub rx_crc_errors: u64,
+    pub rx_frame_errors: u64,
+    pub rx_fifo_errors: u64,
+    pub rx_missed_errors: u64,
+    pub tx_aborted_errors: u64,
+    pub tx_carrier_errors: u64,
+    pub tx_fifo_errors: u64,
+    pub tx_heartbeat_errors: u64,
+    pub tx_window_errors: u64,
+    pub rx_compressed: u64,
+    pub tx_compressed: u64,
+    pub rx_nohandler: u64,
+    pub rx_otherhost_dropped: u64,
+}
+
+/// Nexthop group member (`struct nexthop_grp` in `NHA_GROUP`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NextHopGroupMember {
+    /// Member nexthop object identifier.
+    pub id: u32,
+    /// Member weight minus one (`weight_high` ignored).
+    pub weight: u8,
+}
+
+#[derive(Clone)]
 pub struct AttributeValue<T> {
     pub length: u16,
     pub kind: u16,
     pub value: T,
 }
 
-impl AttributeValue<IPv4> {
+impl<T: Copy + Into<u32>> AttributeValue<T> {
+    /// Integer value widened to `u32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        Some(self.value.into())
+    }
+}
+
+impl AttributeValue<u8> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<u8>> {
+        let mut payload = PacketParser::new(read_payload(parser, length, 1)?);
+        Ok(AttributeValue::<u8> {
+            length,
+            kind,
+            value: payload.read_u8(),
+        })
+    }
+}
+
+impl AttributeValue<i32> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<i32>> {
+        let mut payload = PacketParser::new(read_payload(parser, length, 4)?);
+        Ok(AttributeValue::<i32> {
+            length,
+            kind,
+            value: payload.read_i32(),
+        })
+    }
+}
+
+impl AttributeValue<u32> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<u32>> {
+        let mut payload = PacketParser::new(read_payload(parser, length, 4)?);
+        Ok(AttributeValue::<u32> {
+            length,
+            kind,
+            value: payload.read_u32(),
+        })
+    }
+}
+
+impl AttributeValue<NetIpv4> {
     pub fn from(
         parser: &mut PacketParser,
         length: u16,
         kind: u16,
-    ) -> NetlinkParseResult<AttributeValue<IPv4>> {
-        Ok(AttributeValue::<IPv4> {
-            length: length,
-            kind: kind,
-            value: parser.read_u32(),
+    ) -> NetlinkParseResult<AttributeValue<NetIpv4>> {
+        let payload = read_payload(parser, length, 4)?;
+        Ok(AttributeValue::<NetIpv4> {
+            length,
+            kind,
+            value: NetIpv4([payload[0], payload[1], payload[2], payload[3]]),
         })
     }
 }
 
-impl AttributeValue<IPv6> {
+impl AttributeValue<NetIpv6> {
     pub fn from(
         parser: &mut PacketParser,
         length: u16,
         kind: u16,
-    ) -> NetlinkParseResult<AttributeValue<IPv6>> {
-        Ok(AttributeValue::<IPv6> {
-            length: length,
-            kind: kind,
-            value: [
-                parser.read_u32(),
-                parser.read_u32(),
-                parser.read_u32(),
-                parser.read_u32(),
-            ],
+    ) -> NetlinkParseResult<AttributeValue<NetIpv6>> {
+        let mut value = [0u8; 16];
+        value.copy_from_slice(&read_payload(parser, length, 16)?[..16]);
+        Ok(AttributeValue::<NetIpv6> {
+            length,
+            kind,
+            value: NetIpv6(value),
         })
     }
 }
@@ -72,17 +418,46 @@ impl AttributeValue<Mac> {
         length: u16,
         kind: u16,
     ) -> NetlinkParseResult<AttributeValue<Mac>> {
+        let mut payload = PacketParser::new(read_payload(parser, length, 6)?);
         Ok(AttributeValue::<Mac> {
-            length: length,
-            kind: kind,
-            value: [
-                parser.read_u8(),
-                parser.read_u8(),
-                parser.read_u8(),
-                parser.read_u8(),
-                parser.read_u8(),
-                parser.read_u8(),
-            ],
+            length,
+            kind,
+            value: payload.read_mac(),
+        })
+    }
+}
+
+impl AttributeValue<u16> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<u16>> {
+        let mut payload = PacketParser::new(read_payload(parser, length, 2)?);
+        Ok(AttributeValue::<u16> {
+            length,
+            kind,
+            value: payload.read_u16(),
+        })
+    }
+}
+
+impl AttributeValue<String> {
+    /// Read a string attribute, the trailing NUL characters are removed.
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<String>> {
+        let payload = read_payload(parser, length, 0)?;
+        let end = payload
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(payload.len());
+        Ok(AttributeValue::<String> {
+            length,
+            kind,
+            value: String::from_utf8_lossy(&payload[..end]).into_owned(),
         })
     }
 }
@@ -96,14 +471,450 @@ impl AttributeValue<Vec<u8>> {
         Ok(AttributeValue::<Vec<u8>> {
             length,
             kind,
-            value: parser.read_vec(length as usize),
+            value: read_payload(parser, length, 0)?.to_vec(),
+        })
+    }
+}
+
+/// SR-IOV virtual functions list (`IFLA_VFINFO_LIST`): the decoded
+/// entries and the payload in wire format, serialized as is.
+#[derive(Clone)]
+pub struct VfInfoList {
+    pub vfs: Vec<VfInfo>,
+    pub payload: Vec<u8>,
+}
+
+impl AttributeValue<VfInfoList> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<VfInfoList>> {
+        let payload = read_payload(parser, length, 0)?;
+        let mut list = PacketParser::new(payload);
+
+        let mut vfs = vec![];
+        while list.remaining() > 0 {
+            let (kind, payload) = read_nested_attribute(&mut list)?;
+            if kind == vf_attribute::INFO {
+                vfs.push(VfInfo::from(&mut PacketParser::new(payload))?);
+            }
+        }
+
+        Ok(AttributeValue::<VfInfoList> {
+            length,
+            kind,
+            value: VfInfoList {
+                vfs,
+                payload: payload.to_vec(),
+            },
+        })
+    }
+}
+
+impl AttributeValue<IfMap> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<IfMap>> {
+        // Structure is 28 bytes long plus 4 bytes of tail padding.
+        let mut payload = PacketParser::new(read_payload(parser, length, 28)?);
+        Ok(AttributeValue::<IfMap> {
+            length,
+            kind,
+            value: IfMap {
+                mem_start: payload.read_u64(),
+                mem_end: payload.read_u64(),
+                base_addr: payload.read_u64(),
+                irq: payload.read_u16(),
+                dma: payload.read_u8(),
+                port: payload.read_u8(),
+            },
+        })
+    }
+}
+
+impl AttributeValue<LinkStats64> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<LinkStats64>> {
+        // Kernels before 4.6 report 23 counters.
+        let mut payload = PacketParser::new(read_payload(parser, length, 23 * 8)?);
+        Ok(AttributeValue::<LinkStats64> {
+            length,
+            kind,
+            value: LinkStats64 {
+                rx_packets: payload.read_u64(),
+                tx_packets: payload.read_u64(),
+                rx_bytes: payload.read_u64(),
+                tx_bytes: payload.read_u64(),
+                rx_errors: payload.read_u64(),
+                tx_errors: payload.read_u64(),
+                rx_dropped: payload.read_u64(),
+                tx_dropped: payload.read_u64(),
+                multicast: payload.read_u64(),
+                collisions: payload.read_u64(),
+                rx_length_errors: payload.read_u64(),
+                rx_over_errors: payload.read_u64(),
+                rx_crc_errors: payload.read_u64(),
+                rx_frame_errors: payload.read_u64(),
+                rx_fifo_errors: payload.read_u64(),
+                rx_missed_errors: payload.read_u64(),
+                tx_aborted_errors: payload.read_u64(),
+                tx_carrier_errors: payload.read_u64(),
+                tx_fifo_errors: payload.read_u64(),
+                tx_heartbeat_errors: payload.read_u64(),
+                tx_window_errors: payload.read_u64(),
+                rx_compressed: payload.read_u64(),
+                tx_compressed: payload.read_u64(),
+                rx_nohandler: payload.read_u64(),
+                rx_otherhost_dropped: payload.read_u64(),
+            },
         })
     }
 }
 
+impl AttributeValue<Vec<NextHopGroupMember>> {
+    pub fn from(
+        parser: &mut PacketParser,
+        length: u16,
+        kind: u16,
+    ) -> NetlinkParseResult<AttributeValue<Vec<NextHopGroupMember>>> {
+        let payload = read_payload(parser, length, 0)?;
+        if payload.len() % 8 != 0 {
+            return Err(NetlinkParseError::InvalidAttributeLength);
+        }
+
+        let mut members = PacketParser::new(payload);
+        let mut value = vec![];
+        while members.remaining() > 0 {
+            let id = members.read_u32();
+            let weight = members.read_u8();
+            members.skip(3);
+            value.push(NextHopGroupMember { id, weight });
+        }
+
+        Ok(AttributeValue::<Vec<NextHopGroupMember>> {
+            length,
+            kind,
+            value,
+        })
+    }
+}
+
+#[derive(Clone)]
 pub enum Attribute {
-    IPv4(AttributeValue<IPv4>),
-    IPv6(AttributeValue<IPv6>),
+    IPv4(AttributeValue<NetIpv4>),
+    IPv6(AttributeValue<NetIpv6>),
     Mac(AttributeValue<Mac>),
+    U8(AttributeValue<u8>),
+    U16(AttributeValue<u16>),
+    U32(AttributeValue<u32>),
+    I32(AttributeValue<i32>),
+    String(AttributeValue<String>),
+    /// Nested attributes kept in wire format, decoded on demand.
+    Nested(AttributeValue<Vec<u8>>),
+    VfInfoList(AttributeValue<VfInfoList>),
+    IfMap(AttributeValue<IfMap>),
+    LinkStats64(AttributeValue<LinkStats64>),
+    /// Wireless extensions event (`IFLA_WIRELESS`) in wire format.
+    Wireless(AttributeValue<Vec<u8>>),
+    NextHopGroup(AttributeValue<Vec<NextHopGroupMember>>),
+    /// MPLS label stack entries (`RTA_DST` and `RTA_NEWDST` of MPLS
+    /// routes) in wire format.
+    MplsLabels(AttributeValue<Vec<u8>>),
     Unknown(AttributeValue<Vec<u8>>),
 }
+
+impl Attribute {
+    /// Attribute type.
+    pub fn kind(&self) -> u16 {
+        match self {
+            Attribute::IPv4(attribute) => attribute.kind,
+            Attribute::IPv6(attribute) => attribute.kind,
+            Attribute::Mac(attribute) => attribute.kind,
+            Attribute::U8(attribute) => attribute.kind,
+            Attribute::U16(attribute) => attribute.kind,
+            Attribute::U32(attribute) => attribute.kind,
+            Attribute::I32(attribute) => attribute.kind,
+            Attribute::String(attribute) => attribute.kind,
+            Attribute::Nested(attribute) => attribute.kind,
+            Attribute::VfInfoList(attribute) => attribute.kind,
+            Attribute::IfMap(attribute) => attribute.kind,
+            Attribute::LinkStats64(attribute) => attribute.kind,
+            Attribute::Wireless(attribute) => attribute.kind,
+            Attribute::NextHopGroup(attribute) => attribute.kind,
+            Attribute::MplsLabels(attribute) => attribute.kind,
+            Attribute::Unknown(attribute) => attribute.kind,
+        }
+    }
+
+    /// Attribute payload in wire format.
+    fn payload(&self) -> Vec<u8> {
+        match self {
+            Attribute::IPv4(attribute) => attribute.value.0.to_vec(),
+            Attribute::IPv6(attribute) => attribute.value.0.to_vec(),
+            Attribute::Mac(attribute) => attribute.value.to_vec(),
+            Attribute::U8(attribute) => vec![attribute.value],
+            Attribute::U16(attribute) => attribute.value.to_ne_bytes().to_vec(),
+            Attribute::U32(attribute) => attribute.value.to_ne_bytes().to_vec(),
+            Attribute::I32(attribute) => attribute.value.to_ne_bytes().to_vec(),
+            Attribute::String(attribute) => {
+                let mut payload = attribute.value.as_bytes().to_vec();
+                payload.push(0);
+                payload
+            }
+            Attribute::Nested(attribute) => attribute.value.clone(),
+            Attribute::VfInfoList(attribute) => attribute.value.payload.clone(),
+            Attribute::IfMap(attribute) => {
+                let map = &attribute.value;
+                let mut payload = vec![];
+                payload.extend_from_slice(&map.mem_start.to_ne_bytes());
+                payload.extend_from_slice(&map.mem_end.to_ne_bytes());
+                payload.extend_from_slice(&map.base_addr.to_ne_bytes());
+                payload.extend_from_slice(&map.irq.to_ne_bytes());
+                payload.extend_from_slice(&[map.dma, map.port, 0, 0, 0, 0]);
+                payload
+            }
+            Attribute::LinkStats64(attribute) => {
+                let stats = &attribute.value;
+                [
+                    stats.rx_packets,
+                    stats.tx_packets,
+                    stats.rx_bytes,
+                    stats.tx_bytes,
+                    stats.rx_errors,
+                    stats.tx_errors,
+                    stats.rx_dropped,
+                    stats.tx_dropped,
+                    stats.multicast,
+                    stats.collisions,
+                    stats.rx_length_errors,
+                    stats.rx_over_errors,
+                    stats.rx_crc_errors,
+                    stats.rx_frame_errors,
+                    stats.rx_fifo_errors,
+                    stats.rx_missed_errors,
+                    stats.tx_aborted_errors,
+                    stats.tx_carrier_errors,
+                    stats.tx_fifo_errors,
+                    stats.tx_heartbeat_errors,
+                    stats.tx_window_errors,
+                    stats.rx_compressed,
+                    stats.tx_compressed,
+                    stats.rx_nohandler,
+                    stats.rx_otherhost_dropped,
+                ]
+                .iter()
+                .flat_map(|counter| counter.to_ne_bytes())
+                .collect()
+            }
+            Attribute::Wireless(attribute) => attribute.value.clone(),
+            Attribute::MplsLabels(attribute) => attribute.value.clone(),
+            Attribute::NextHopGroup(attribute) => attribute
+                .value
+                .iter()
+                .flat_map(|member| {
+                    let mut entry = member.id.to_ne_bytes().to_vec();
+                    entry.extend_from_slice(&[member.weight, 0, 0, 0]);
+                    entry
+                })
+                .collect(),
+            Attribute::Unknown(attribute) => attribute.value.clone(),
+        }
+    }
+
+    /// Integer attributes value widened to `u32`, `None` for the other
+    /// attribute types (addresses, strings etc...).
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Attribute::U8(attribute) => attribute.as_u32(),
+            Attribute::U16(attribute) => attribute.as_u32(),
+            Attribute::U32(attribute) => attribute.as_u32(),
+            _ => None,
+        }
+    }
+
+    /// Serialized attribute size including header and padding.
+    pub fn length(&self) -> usize {
+        (ATTRIBUTE_HEADER_SIZE + self.payload().len()).next_multiple_of(4)
+    }
+
+    pub fn to_array(&self, writer: &mut PacketWriter) {
+        let payload = self.payload();
+        let length = ATTRIBUTE_HEADER_SIZE + payload.len();
+
+        writer.write_u16(length as u16);
+        writer.write_u16(self.kind());
+        writer.write_bytes(&payload);
+        writer.write_bytes(&[0u8; 3][..length.next_multiple_of(4) - length]);
+    }
+}
+
+#[cfg(test)]
+mod route_attribute_test {
+    use super::*;
+
+    /// Returns the attribute bytes (without the length/type header)
+    /// followed by a trailing marker.
+    fn payload(value: &[u8], padding: usize) -> Vec<u8> {
+        let mut bytes = value.to_vec();
+        bytes.resize(value.len() + padding, 0);
+        bytes.extend_from_slice(&0xdeadbeefu32.to_ne_bytes());
+        bytes
+    }
+
+    #[test]
+    fn mac_length() {
+        let bytes = payload(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x01], 2);
+
+        let mut parser = PacketParser::new(&bytes);
+        let attribute = AttributeValue::<Mac>::from(&mut parser, 10, libc::IFLA_ADDRESS).unwrap();
+        assert_eq!(attribute.value, [0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(parser.read_u32(), 0xdeadbeef);
+
+        let mut parser = PacketParser::new(&bytes);
+        assert!(matches!(
+            AttributeValue::<Mac>::from(&mut parser, 8, libc::IFLA_ADDRESS),
+            Err(NetlinkParseError::InvalidAttributeLength)
+        ));
+    }
+
+    #[test]
+    fn ipv4_length() {
+        let bytes = payload(&[127, 0, 0, 1], 0);
+
+        let mut parser = PacketParser::new(&bytes);
+        let attribute = AttributeValue::<NetIpv4>::from(&mut parser, 8, libc::IFA_ADDRESS).unwrap();
+        assert_eq!(attribute.value.to_ipv4addr(), Ipv4Addr::LOCALHOST);
+        assert_eq!(parser.read_u32(), 0xdeadbeef);
+
+        let mut parser = PacketParser::new(&bytes);
+        assert!(matches!(
+            AttributeValue::<NetIpv4>::from(&mut parser, 6, libc::IFA_ADDRESS),
+            Err(NetlinkParseError::InvalidAttributeLength)
+        ));
+    }
+
+    #[test]
+    fn ipv6_length() {
+        let bytes = payload(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 0);
+
+        let mut parser = PacketParser::new(&bytes);
+        let attribute =
+            AttributeValue::<NetIpv6>::from(&mut parser, 20, libc::IFA_ADDRESS).unwrap();
+        assert_eq!(
+            attribute.value.to_ipv6addr(),
+            "fe80::1".parse::<Ipv6Addr>().unwrap()
+        );
+        assert_eq!(parser.read_u32(), 0xdeadbeef);
+
+        let mut parser = PacketParser::new(&bytes);
+        assert!(matches!(
+            AttributeValue::<NetIpv6>::from(&mut parser, 8, libc::IFA_ADDRESS),
+            Err(NetlinkParseError::InvalidAttributeLength)
+        ));
+    }
+
+    #[test]
+    fn ipv4_round_trip() {
+        let attribute = Attribute::IPv4(AttributeValue {
+            length: 0,
+            kind: libc::IFA_ADDRESS,
+            value: NetIpv4::from_ipv4addr(Ipv4Addr::LOCALHOST),
+        });
+        let bytes = nested_payload(&[attribute]);
+        // Network byte order on any host.
+        assert_eq!(&bytes[4..], &[127, 0, 0, 1]);
+
+        let mut parser = PacketParser::new(&bytes);
+        let (length, kind) = (parser.read_u16(), parser.read_u16());
+        let attribute = AttributeValue::<NetIpv4>::from(&mut parser, length, kind).unwrap();
+        assert_eq!(attribute.value.to_ipv4addr(), Ipv4Addr::LOCALHOST);
+        assert_eq!(attribute.value.to_ipv4addr().to_string(), "127.0.0.1");
+    }
+
+    #[test]
+    fn as_u32() {
+        let bytes = payload(&[libc::IF_OPER_UP as u8], 3);
+        let mut parser = PacketParser::new(&bytes);
+        let attribute = Attribute::U8(
+            AttributeValue::<u8>::from(&mut parser, 5, libc::IFLA_OPERSTATE).unwrap(),
+        );
+        assert_eq!(attribute.as_u32(), Some(libc::IF_OPER_UP as u32));
+        assert_eq!(parser.read_u32(), 0xdeadbeef);
+
+        let bytes = payload(&1500u32.to_ne_bytes(), 0);
+        let mut parser = PacketParser::new(&bytes);
+        let attribute =
+            Attribute::U32(AttributeValue::<u32>::from(&mut parser, 8, libc::IFLA_MTU).unwrap());
+        assert_eq!(attribute.as_u32(), Some(1500));
+
+        let attribute = Attribute::Mac(AttributeValue::<Mac> {
+            length: 10,
+            kind: libc::IFLA_ADDRESS,
+            value: [0x02, 0, 0, 0, 0, 0x01],
+        });
+        assert_eq!(attribute.as_u32(), None);
+    }
+
+    /// Attribute with type `kind` flagged as nested wrapping `payload`.
+    fn nested(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&((ATTRIBUTE_HEADER_SIZE + payload.len()) as u16).to_ne_bytes());
+        bytes.extend_from_slice(&(kind | libc::NLA_F_NESTED as u16).to_ne_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn attribute_tree() {
+        let mut leaf = vec![];
+        leaf.extend_from_slice(&8u16.to_ne_bytes());
+        leaf.extend_from_slice(&3u16.to_ne_bytes());
+        leaf.extend_from_slice(&7u32.to_ne_bytes());
+        let bytes = nested(1, &nested(2, &leaf));
+
+        let tree = parse_attribute_tree(&bytes, ATTRIBUTE_DEFAULT_NESTING_DEPTH).unwrap();
+        assert_eq!(
+            tree,
+            vec![AttributeNode::Nested {
+                kind: 1,
+                attributes: vec![AttributeNode::Nested {
+                    kind: 2,
+                    attributes: vec![AttributeNode::Value {
+                        kind: 3,
+                        payload: &7u32.to_ne_bytes(),
+                    }],
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn attribute_tree_too_deep() {
+        let mut bytes = vec![];
+        for _ in 0..ATTRIBUTE_DEFAULT_NESTING_DEPTH {
+            bytes = nested(1, &bytes);
+        }
+        assert!(parse_attribute_tree(&bytes, ATTRIBUTE_DEFAULT_NESTING_DEPTH).is_ok());
+
+        let mut bytes = nested(1, &bytes);
+        assert!(matches!(
+            parse_attribute_tree(&bytes, ATTRIBUTE_DEFAULT_NESTING_DEPTH),
+            Err(NetlinkParseError::NestingTooDeep)
+        ));
+
+        // As deep as the 16 bit attribute length allows.
+        while bytes.len() + ATTRIBUTE_HEADER_SIZE <= u16::MAX as usize {
+            bytes = nested(1, &bytes);
+        }
+        assert!(matches!(
+            parse_attribute_tree(&bytes, 16),
+            Err(NetlinkParseError::NestingTooDeep)
+        ));
+    }
+}
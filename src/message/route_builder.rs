@@ -0,0 +1,1360 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use super::{
+    route::{
+        address_attribute, family, message_type, nsid_attribute, protocol, route_attribute_type,
+        route_type, scope, Address, AddressMessage, Link, LinkMessage, LinkStatsExtended,
+        MessageType, NextHopMessage, NextHopObject, NsId, NsIdMessage, Route, RouteMessage,
+        StatsMessage,
+    },
+    route::{veth_attribute, vlan_attribute},
+    route_attribute::{nested_payload, Attribute, AttributeValue, NetIpv4, NetIpv6},
+    BuildError, NetlinkHeader, NetlinkMessage, NetlinkPayload, PortId,
+};
+use std::mem;
+use std::net::IpAddr;
+
+/// Interface message (`RTM_GETLINK`, `RTM_NEWLINK` and `RTM_SETLINK`)
+/// builder.
+pub struct LinkBuilder {
+    kind: u16,
+    flags: u16,
+    link: Link,
+}
+
+impl LinkBuilder {
+    fn new(kind: u16, flags: i32, index: i32) -> LinkBuilder {
+        LinkBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            link: Link {
+                message: LinkMessage {
+                    family: family::UNSPEC,
+                    kind: 0,
+                    index,
+                    flags: 0,
+                    change: 0,
+                },
+                attributes: vec![],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Change the interface with index `index` (`RTM_SETLINK`).
+    pub fn set_link(index: i32) -> LinkBuilder {
+        LinkBuilder::new(libc::RTM_SETLINK, 0, index)
+    }
+
+    /// Create the interface `name` of type `kind` (`ip link add X type
+    /// Y`), e.g. "dummy" or "bridge".
+    pub fn add_link(name: &str, kind: &str) -> LinkBuilder {
+        LinkBuilder::add_link_info(name, kind, &[])
+    }
+
+    /// Create the dummy interface `name` (`ip link add X type dummy`).
+    pub fn create_dummy(name: &str) -> LinkBuilder {
+        LinkBuilder::add_link(name, "dummy")
+    }
+
+    /// Create the veth pair `name` and `peer` (`ip link add X type veth
+    /// peer name Y`).
+    pub fn create_veth(name: &str, peer: &str) -> LinkBuilder {
+        // The peer is described by a whole interface message.
+        let mut info = vec![0u8; mem::size_of::<LinkMessage>()];
+        info.extend(nested_payload(&[Attribute::String(AttributeValue::<
+            String,
+        > {
+            length: 0,
+            kind: libc::IFLA_IFNAME,
+            value: peer.to_string(),
+        })]));
+        let data = [Attribute::Nested(AttributeValue::<Vec<u8>> {
+            length: 0,
+            kind: veth_attribute::PEER,
+            value: info,
+        })];
+
+        LinkBuilder::add_link_info(name, "veth", &data)
+    }
+
+    /// Delete the interface `name` (`ip link del X`).
+    pub fn del_link(name: &str) -> LinkBuilder {
+        let mut builder = LinkBuilder::new(libc::RTM_DELLINK, 0, 0);
+        builder
+            .link
+            .attributes
+            .push(Attribute::String(AttributeValue::<String> {
+                length: 0,
+                kind: libc::IFLA_IFNAME,
+                value: name.to_string(),
+            }));
+        builder
+    }
+
+    /// Add the alternative name `name` to the interface with index
+    /// `index` (`ip link property add dev X altname Y`).
+    pub fn add_alt_name(index: i32, name: &str) -> LinkBuilder {
+        LinkBuilder::new(
+            message_type::NEWLINKPROP,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            index,
+        )
+        .alt_name(name)
+    }
+
+    /// Remove the alternative name `name` from the interface with index
+    /// `index` (`ip link property del dev X altname Y`).
+    pub fn del_alt_name(index: i32, name: &str) -> LinkBuilder {
+        LinkBuilder::new(message_type::DELLINKPROP, 0, index).alt_name(name)
+    }
+
+    fn alt_name(mut self, name: &str) -> LinkBuilder {
+        let names = nested_payload(&[Attribute::String(AttributeValue::<String> {
+            length: 0,
+            kind: libc::IFLA_ALT_IFNAME,
+            value: name.to_string(),
+        })]);
+        self.link
+            .attributes
+            .push(Attribute::Nested(AttributeValue::<Vec<u8>> {
+                length: 0,
+                kind: libc::IFLA_PROP_LIST | libc::NLA_F_NESTED as u16,
+                value: names,
+            }));
+        self
+    }
+
+    fn add_link_info(name: &str, kind: &str, data: &[Attribute]) -> LinkBuilder {
+        let mut info = vec![Attribute::String(AttributeValue::<String> {
+            length: 0,
+            kind: libc::IFLA_INFO_KIND,
+            value: kind.to_string(),
+        })];
+        if !data.is_empty() {
+            info.push(Attribute::Nested(AttributeValue::<Vec<u8>> {
+                length: 0,
+                kind: libc::IFLA_INFO_DATA,
+                value: nested_payload(data),
+            }));
+        }
+
+        let mut builder =
+            LinkBuilder::new(libc::RTM_NEWLINK, libc::NLM_F_CREATE | libc::NLM_F_EXCL, 0);
+        builder.link.attributes = vec![
+            Attribute::String(AttributeValue::<String> {
+                length: 0,
+                kind: libc::IFLA_IFNAME,
+                value: name.to_string(),
+            }),
+            Attribute::Nested(AttributeValue::<Vec<u8>> {
+                length: 0,
+                kind: libc::IFLA_LINKINFO,
+                value: nested_payload(&info),
+            }),
+        ];
+        builder
+    }
+
+    /// Create the VLAN interface `name` with VLAN ID `id` on top of the
+    /// interface with index `parent` (`ip link add link X name Y type vlan
+    /// id Z`).
+    pub fn add_vlan(name: &str, parent: i32, id: u16) -> LinkBuilder {
+        let data = [Attribute::U16(AttributeValue::<u16> {
+            length: 0,
+            kind: vlan_attribute::ID,
+            value: id,
+        })];
+
+        let mut builder = LinkBuilder::add_link_info(name, "vlan", &data);
+        builder
+            .link
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: libc::IFLA_LINK,
+                value: parent as u32,
+            }));
+        builder
+    }
+
+    /// Ask the kernel to echo the resulting object back (`NLM_F_ECHO`), see
+    /// [`crate::connection::NetlinkConnection::execute_echo`].
+    pub fn echo(mut self) -> LinkBuilder {
+        self.flags |= libc::NLM_F_ECHO as u16;
+        self
+    }
+
+    /// Set the interface administrative state to up (`IFF_UP`).
+    pub fn up(mut self) -> LinkBuilder {
+        self.link.message.flags |= libc::IFF_UP as u32;
+        self.link.message.change |= libc::IFF_UP as u32;
+        self
+    }
+
+    /// Set the interface administrative state to down (`IFF_UP`).
+    pub fn down(mut self) -> LinkBuilder {
+        self.link.message.flags &= !(libc::IFF_UP as u32);
+        self.link.message.change |= libc::IFF_UP as u32;
+        self
+    }
+
+    /// Set the interface alias (`IFLA_IFALIAS`).
+    pub fn alias(mut self, alias: &str) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::String(AttributeValue::<String> {
+                length: 0,
+                kind: libc::IFLA_IFALIAS,
+                value: alias.to_string(),
+            }));
+        self
+    }
+
+    /// Extended information filter (`IFLA_EXT_MASK`), e.g.
+    /// `libc::RTEXT_FILTER_VF` to include the SR-IOV virtual functions in
+    /// dumps like `ip link show` does.
+    pub fn ext_mask(mut self, mask: u32) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: libc::IFLA_EXT_MASK,
+                value: mask,
+            }));
+        self
+    }
+
+    /// Run the request against the namespace with ID `id`
+    /// (`IFLA_TARGET_NETNSID`), e.g. to dump the interfaces of another
+    /// namespace without switching to it.
+    pub fn target_netnsid(mut self, id: i32) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::I32(AttributeValue::<i32> {
+                length: 0,
+                kind: libc::IFLA_TARGET_NETNSID,
+                value: id,
+            }));
+        self
+    }
+
+    /// Set the alias of interface with index `index`
+    /// (`ip link set dev X alias Y`).
+    pub fn set_alias(index: i32, alias: &str) -> LinkBuilder {
+        LinkBuilder::set_link(index).alias(alias)
+    }
+
+    /// Set the interface MTU (`IFLA_MTU`).
+    pub fn mtu(mut self, mtu: u32) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: libc::IFLA_MTU,
+                value: mtu,
+            }));
+        self
+    }
+
+    /// Set the MTU of interface with index `index`
+    /// (`ip link set dev X mtu Y`).
+    pub fn set_mtu(index: i32, mtu: u32) -> LinkBuilder {
+        LinkBuilder::set_link(index).mtu(mtu)
+    }
+
+    /// Set the interface transmit queue length (`IFLA_TXQLEN`).
+    pub fn txqlen(mut self, length: u32) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: libc::IFLA_TXQLEN,
+                value: length,
+            }));
+        self
+    }
+
+    /// Interface group (`IFLA_GROUP`).
+    ///
+    /// Moves the interface to the group on changes. Link dumps (see
+    /// [`get_links_request`]) are not filtered by the kernel: check
+    /// [`Link::group`] on the replies, e.g. with
+    /// [`crate::interface::enumerate_group`].
+    pub fn group(mut self, group: u32) -> LinkBuilder {
+        self.link
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: libc::IFLA_GROUP,
+                value: group,
+            }));
+        self
+    }
+
+    /// Set the transmit queue length of interface with index `index`
+    /// (`ip link set dev X txqueuelen Y`).
+    pub fn set_txqlen(index: i32, length: u32) -> LinkBuilder {
+        LinkBuilder::set_link(index).txqlen(length)
+    }
+
+    /// Same as [`LinkBuilder::set_mtu`], but validates `mtu` against the
+    /// range reported by the kernel for `link` (`IFLA_MIN_MTU` and
+    /// `IFLA_MAX_MTU`) when available.
+    pub fn set_mtu_checked(link: &Link, mtu: u32) -> Result<LinkBuilder, BuildError> {
+        let minimum = link.min_mtu().unwrap_or(0);
+        let maximum = link.max_mtu().unwrap_or(u32::MAX);
+        if mtu < minimum || mtu > maximum {
+            return Err(BuildError::MtuOutOfRange {
+                mtu,
+                minimum,
+                maximum,
+            });
+        }
+
+        Ok(LinkBuilder::set_mtu(link.message.index, mtu))
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<LinkMessage>()
+            + self
+                .link
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Link(self.link)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request the interface with index `index` (`RTM_GETLINK`).
+pub fn get_link_request(index: i32) -> LinkBuilder {
+    LinkBuilder::new(libc::RTM_GETLINK, 0, index)
+}
+
+/// Request all interfaces (`RTM_GETLINK` dump).
+pub fn get_links_request() -> LinkBuilder {
+    let mut builder = LinkBuilder::new(libc::RTM_GETLINK, libc::NLM_F_DUMP, 0);
+    // Same as `ip link show`, the kernel handles it as `AF_UNSPEC`.
+    builder.link.message.family = family::PACKET;
+    builder
+}
+
+/// Interface address message (`RTM_GETADDR` and `RTM_NEWADDR`) builder.
+pub struct AddressBuilder {
+    kind: u16,
+    flags: u16,
+    address: Address,
+}
+
+impl AddressBuilder {
+    fn new(kind: u16, flags: i32) -> AddressBuilder {
+        AddressBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            address: Address {
+                message: AddressMessage {
+                    family: family::UNSPEC,
+                    prefix_length: 0,
+                    flags: 0,
+                    scope: 0,
+                    index: 0,
+                },
+                attributes: vec![],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Add the address `address`/`prefix_length` to the interface with
+    /// index `index` (`ip address add X/Y dev Z`).
+    pub fn add_address(index: u32, address: IpAddr, prefix_length: u8) -> AddressBuilder {
+        let mut builder =
+            AddressBuilder::new(libc::RTM_NEWADDR, libc::NLM_F_CREATE | libc::NLM_F_EXCL);
+        builder.address.message.family = match address {
+            IpAddr::V4(_) => family::INET,
+            IpAddr::V6(_) => family::INET6,
+        };
+        builder.address.message.prefix_length = prefix_length;
+        builder.address.message.index = index;
+        // Without a peer the local address is also the prefix address.
+        builder.address.attributes = vec![
+            address_attribute(libc::IFA_LOCAL, address),
+            address_attribute(libc::IFA_ADDRESS, address),
+        ];
+        builder
+    }
+
+    /// Run the dump against the namespace with ID `id`
+    /// (`IFA_TARGET_NETNSID`).
+    ///
+    /// The kernel only reads the attribute from sockets with strict
+    /// checking enabled (`libc::NETLINK_GET_STRICT_CHK`, see
+    /// [`crate::socket::NetlinkSocket::set_netlink_option`]) and
+    /// otherwise dumps the socket namespace. Strict checking also rejects
+    /// dumps with non zero prefix length, flags or scope, which
+    /// [`get_addresses_request`] never sets.
+    pub fn target_netnsid(mut self, id: i32) -> AddressBuilder {
+        self.address
+            .attributes
+            .push(Attribute::I32(AttributeValue::<i32> {
+                length: 0,
+                kind: address_attribute::TARGET_NETNSID,
+                value: id,
+            }));
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<AddressMessage>()
+            + self
+                .address
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Address(self.address)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request all interface addresses (`RTM_GETADDR` dump).
+pub fn get_addresses_request() -> AddressBuilder {
+    AddressBuilder::new(libc::RTM_GETADDR, libc::NLM_F_DUMP)
+}
+
+/// Nexthop object message (`RTM_GETNEXTHOP`, `RTM_NEWNEXTHOP` and
+/// `RTM_DELNEXTHOP`) builder.
+pub struct NextHopBuilder {
+    kind: u16,
+    flags: u16,
+    nexthop: NextHopObject,
+}
+
+impl NextHopBuilder {
+    fn new(kind: u16, flags: i32) -> NextHopBuilder {
+        NextHopBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            nexthop: NextHopObject {
+                message: NextHopMessage {
+                    family: family::UNSPEC,
+                    scope: scope::UNIVERSE,
+                    protocol: protocol::UNSPEC,
+                    reserved: 0,
+                    flags: 0,
+                },
+                attributes: vec![],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Ask the kernel to echo the resulting object back (`NLM_F_ECHO`), see
+    /// [`crate::connection::NetlinkConnection::execute_echo`].
+    pub fn echo(mut self) -> NextHopBuilder {
+        self.flags |= libc::NLM_F_ECHO as u16;
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<NextHopMessage>()
+            + self
+                .nexthop
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::NextHop(self.nexthop)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request all nexthop objects (`RTM_GETNEXTHOP` dump).
+pub fn get_nexthops_request() -> NextHopBuilder {
+    NextHopBuilder::new(message_type::GETNEXTHOP, libc::NLM_F_DUMP)
+}
+
+/// Route message (`RTM_NEWROUTE`) builder.
+pub struct RouteBuilder {
+    kind: u16,
+    flags: u16,
+    route: Route,
+}
+
+fn address_attribute(kind: u16, address: IpAddr) -> Attribute {
+    match address {
+        IpAddr::V4(address) => Attribute::IPv4(AttributeValue {
+            length: 0,
+            kind,
+            value: NetIpv4::from_ipv4addr(address),
+        }),
+        IpAddr::V6(address) => Attribute::IPv6(AttributeValue {
+            length: 0,
+            kind,
+            value: NetIpv6::from_ipv6addr(address),
+        }),
+    }
+}
+
+impl RouteBuilder {
+    fn new(kind: u16, flags: i32, destination: IpAddr, prefix_length: u8) -> RouteBuilder {
+        let family = match destination {
+            IpAddr::V4(_) => family::INET,
+            IpAddr::V6(_) => family::INET6,
+        };
+
+        RouteBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            route: Route {
+                message: RouteMessage {
+                    family,
+                    destination_prefix_length: prefix_length,
+                    source_prefix_length: 0,
+                    type_of_service: 0,
+                    table: libc::RT_TABLE_MAIN,
+                    protocol: libc::RTPROT_BOOT,
+                    scope: scope::UNIVERSE,
+                    kind: route_type::UNICAST,
+                    flags: 0,
+                },
+                attributes: vec![address_attribute(libc::RTA_DST, destination)],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Add the route to `destination`/`prefix_length` in the main table
+    /// with protocol `RTPROT_BOOT` (`ip route add X/Y`), select the nexthop with
+    /// [`RouteBuilder::gateway`] and [`RouteBuilder::oif`] or
+    /// [`RouteBuilder::nexthop_id`].
+    pub fn add_route(destination: IpAddr, prefix_length: u8) -> RouteBuilder {
+        RouteBuilder::new(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            destination,
+            prefix_length,
+        )
+    }
+
+    /// Add the MPLS route for incoming label `label` (`ip -f mpls route
+    /// add X`), select the nexthop with [`RouteBuilder::oif`].
+    pub fn add_mpls_route(label: u32) -> RouteBuilder {
+        let mut builder = RouteBuilder::new(
+            libc::RTM_NEWROUTE,
+            libc::NLM_F_CREATE | libc::NLM_F_EXCL,
+            IpAddr::from([0, 0, 0, 0]),
+            20,
+        );
+        builder.route.message.family = family::MPLS;
+        // Single label stack entry with the bottom of stack bit.
+        builder.route.attributes = vec![Attribute::MplsLabels(AttributeValue::<Vec<u8>> {
+            length: 0,
+            kind: libc::RTA_DST,
+            value: ((label << 12) | 0x100).to_be_bytes().to_vec(),
+        })];
+        builder
+    }
+
+    /// Copy the label TTL to the IP header when popping the last label
+    /// (`RTA_TTL_PROPAGATE`), only for MPLS routes.
+    pub fn ttl_propagate(mut self, enabled: bool) -> RouteBuilder {
+        self.route.attributes.push(Attribute::U8(AttributeValue {
+            length: 0,
+            kind: route_attribute_type::TTL_PROPAGATE,
+            value: enabled as u8,
+        }));
+        self
+    }
+
+    /// Look up the route the kernel would use to reach `destination`
+    /// (`ip route get X`), see [`NetlinkMessage::get_route_to`].
+    pub fn get_route(destination: IpAddr) -> RouteBuilder {
+        let prefix_length = match destination {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let mut builder = RouteBuilder::new(libc::RTM_GETROUTE, 0, destination, prefix_length);
+        // Strict checking rejects lookups with these set.
+        builder.route.message.table = 0;
+        builder.route.message.protocol = 0;
+        builder.route.message.scope = 0;
+        builder.route.message.kind = 0;
+        builder
+    }
+
+    /// Use the routing table `table` instead of the main table
+    /// (`RTA_TABLE`).
+    pub fn table(mut self, table: u32) -> RouteBuilder {
+        self.route.message.table = u8::try_from(table).unwrap_or(libc::RT_TABLE_COMPAT);
+        self.route.attributes.push(Attribute::U32(AttributeValue {
+            length: 0,
+            kind: libc::RTA_TABLE,
+            value: table,
+        }));
+        self
+    }
+
+    /// Firewall mark (`RTA_MARK`), route lookups (see
+    /// [`RouteBuilder::get_route`]) match the rules of packets with this
+    /// mark (`ip route get X mark Y`).
+    pub fn mark(mut self, mark: u32) -> RouteBuilder {
+        self.route.attributes.push(Attribute::U32(AttributeValue {
+            length: 0,
+            kind: libc::RTA_MARK,
+            value: mark,
+        }));
+        self
+    }
+
+    /// Nexthop gateway (`RTA_GATEWAY`).
+    pub fn gateway(mut self, gateway: IpAddr) -> RouteBuilder {
+        self.route
+            .attributes
+            .push(address_attribute(libc::RTA_GATEWAY, gateway));
+        self
+    }
+
+    /// Nexthop output interface (`RTA_OIF`).
+    pub fn oif(mut self, index: u32) -> RouteBuilder {
+        self.route.attributes.push(Attribute::U32(AttributeValue {
+            length: 0,
+            kind: libc::RTA_OIF,
+            value: index,
+        }));
+        self
+    }
+
+    /// Use the nexthop object `id` (`RTA_NH_ID`), see [`NextHopBuilder`].
+    ///
+    /// Routes with a nexthop object can't have a gateway or output
+    /// interface: [`NetlinkMessage::validate`] fails with
+    /// [`BuildError::ConflictingNextHop`].
+    pub fn nexthop_id(mut self, id: u32) -> RouteBuilder {
+        self.route.attributes.push(Attribute::U32(AttributeValue {
+            length: 0,
+            kind: route_attribute_type::NH_ID,
+            value: id,
+        }));
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<RouteMessage>()
+            + self
+                .route
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Route(self.route)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request all routes of all tables (`RTM_GETROUTE` dump).
+pub fn get_routes_request() -> RouteBuilder {
+    let mut builder = RouteBuilder::new(
+        libc::RTM_GETROUTE,
+        libc::NLM_F_DUMP,
+        IpAddr::from([0, 0, 0, 0]),
+        0,
+    );
+    builder.route.message = RouteMessage {
+        family: family::UNSPEC,
+        destination_prefix_length: 0,
+        source_prefix_length: 0,
+        type_of_service: 0,
+        table: 0,
+        protocol: 0,
+        scope: 0,
+        kind: 0,
+        flags: 0,
+    };
+    builder.route.attributes.clear();
+    builder
+}
+
+/// Network namespace ID message (`RTM_GETNSID`) builder.
+pub struct NsIdBuilder {
+    kind: u16,
+    flags: u16,
+    nsid: NsId,
+}
+
+impl NsIdBuilder {
+    fn new(kind: u16, flags: i32) -> NsIdBuilder {
+        NsIdBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            nsid: NsId {
+                message: NsIdMessage {
+                    family: family::UNSPEC,
+                },
+                attributes: vec![],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Namespace referenced by the open file descriptor `fd`
+    /// (`NETNSA_FD`).
+    pub fn fd(mut self, fd: i32) -> NsIdBuilder {
+        self.nsid
+            .attributes
+            .push(Attribute::I32(AttributeValue::<i32> {
+                length: 0,
+                kind: nsid_attribute::FD,
+                value: fd,
+            }));
+        self
+    }
+
+    /// Namespace of the process `pid` (`NETNSA_PID`).
+    pub fn pid(mut self, pid: u32) -> NsIdBuilder {
+        self.nsid
+            .attributes
+            .push(Attribute::U32(AttributeValue::<u32> {
+                length: 0,
+                kind: nsid_attribute::PID,
+                value: pid,
+            }));
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<NsIdMessage>().next_multiple_of(4)
+            + self
+                .nsid
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::NsId(self.nsid)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request the ID of a namespace (`RTM_GETNSID`), select the namespace
+/// with [`NsIdBuilder::fd`] or [`NsIdBuilder::pid`].
+pub fn get_nsid_request() -> NsIdBuilder {
+    NsIdBuilder::new(libc::RTM_GETNSID, 0)
+}
+
+/// Interface statistics message (`RTM_GETSTATS`) builder.
+pub struct StatsBuilder {
+    kind: u16,
+    flags: u16,
+    stats: LinkStatsExtended,
+}
+
+impl StatsBuilder {
+    fn new(kind: u16, flags: i32, index: u32, filter_mask: u32) -> StatsBuilder {
+        StatsBuilder {
+            kind,
+            flags: (libc::NLM_F_REQUEST | flags) as u16,
+            stats: LinkStatsExtended {
+                message: StatsMessage {
+                    family: family::UNSPEC,
+                    index,
+                    filter_mask,
+                },
+                attributes: vec![],
+                unknown: vec![],
+            },
+        }
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        let length = mem::size_of::<NetlinkHeader>()
+            + mem::size_of::<StatsMessage>()
+            + self
+                .stats
+                .attributes
+                .iter()
+                .map(Attribute::length)
+                .sum::<usize>();
+
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: length as u32,
+                kind: self.kind,
+                flags: self.flags,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::Route(MessageType::Stats(self.stats)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+/// Request the statistics of the interface with index `index`
+/// (`RTM_GETSTATS`), `filter_mask` selects the reported attributes (see
+/// [`crate::message::route::stats_attribute::filter_bit`]).
+pub fn get_stats_request(index: u32, filter_mask: u32) -> StatsBuilder {
+    StatsBuilder::new(message_type::GETSTATS, 0, index, filter_mask)
+}
+
+#[cfg(test)]
+mod route_builder_test {
+    use super::*;
+    use crate::message::route_attribute::visit_attributes;
+    use crate::message::{ParseOptions, NETLINK_MESSAGE_MAXIMUM_SIZE};
+
+    fn parse_link(bytes: &[u8]) -> Link {
+        match NetlinkMessage::from(bytes) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Link(link)),
+                ..
+            }) => link,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn set_alias() {
+        let message = LinkBuilder::set_alias(2, "uplink").build();
+        assert_eq!(message.header.kind, libc::RTM_SETLINK);
+        assert_eq!(message.header.length, 16 + 16 + 12);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 16 + 12);
+
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.message.index, 2);
+        assert_eq!(link.alias(), Some("uplink"));
+    }
+
+    #[test]
+    fn set_txqlen() {
+        let message = LinkBuilder::set_txqlen(3, 1000).build();
+        assert_eq!(message.header.kind, libc::RTM_SETLINK);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 16 + 8);
+
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.message.index, 3);
+        assert_eq!(link.txqlen(), Some(1000));
+    }
+
+    #[test]
+    fn set_link_group() {
+        let message = LinkBuilder::set_link(3).group(5).build();
+        assert_eq!(message.header.kind, libc::RTM_SETLINK);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 16 + 8);
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.message.index, 3);
+        assert_eq!(link.group(), Some(5));
+    }
+
+    #[test]
+    fn set_mtu_checked() {
+        let link = Link {
+            message: LinkMessage {
+                family: family::UNSPEC,
+                kind: libc::ARPHRD_ETHER,
+                index: 3,
+                flags: 0,
+                change: 0,
+            },
+            attributes: vec![
+                Attribute::U32(AttributeValue::<u32> {
+                    length: 8,
+                    kind: libc::IFLA_MIN_MTU,
+                    value: 68,
+                }),
+                Attribute::U32(AttributeValue::<u32> {
+                    length: 8,
+                    kind: libc::IFLA_MAX_MTU,
+                    value: 9000,
+                }),
+            ],
+            unknown: vec![],
+        };
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let message = LinkBuilder::set_mtu_checked(&link, 9000).unwrap().build();
+        let length = message.to_array(&mut bytes);
+        let parsed = parse_link(&bytes[..length]);
+        assert_eq!(parsed.message.index, 3);
+        assert_eq!(parsed.mtu(), Some(9000));
+
+        assert!(matches!(
+            LinkBuilder::set_mtu_checked(&link, 9001),
+            Err(BuildError::MtuOutOfRange {
+                mtu: 9001,
+                minimum: 68,
+                maximum: 9000
+            })
+        ));
+        assert!(LinkBuilder::set_mtu_checked(&link, 67).is_err());
+    }
+
+    #[test]
+    fn validate_length_mismatch() {
+        let mut message = LinkBuilder::set_alias(2, "uplink").build();
+        assert!(message.validate().is_ok());
+
+        message.header.length += 4;
+        assert!(matches!(
+            message.validate(),
+            Err(BuildError::LengthMismatch {
+                length: 48,
+                serialized: 44
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_attribute_overflow() {
+        let message = LinkBuilder::set_alias(2, &"a".repeat(u16::MAX as usize)).build();
+        assert!(matches!(
+            message.validate(),
+            Err(BuildError::AttributeTooLarge {
+                kind: libc::IFLA_IFALIAS,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn get_links_target_netnsid() {
+        let message = get_links_request().target_netnsid(3).build();
+        assert_eq!(message.header.kind, libc::RTM_GETLINK);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 16 + 8);
+
+        let mut attribute = 8u16.to_ne_bytes().to_vec();
+        attribute.extend_from_slice(&libc::IFLA_TARGET_NETNSID.to_ne_bytes());
+        attribute.extend_from_slice(&3i32.to_ne_bytes());
+        assert_eq!(&bytes[32..length], attribute.as_slice());
+    }
+
+    #[test]
+    fn get_addresses_target_netnsid() {
+        let message = get_addresses_request().target_netnsid(2).build();
+        assert_eq!(message.header.kind, libc::RTM_GETADDR);
+        assert_eq!(
+            message.header.flags,
+            (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16
+        );
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 8 + 8);
+        // Strict checking requires a zeroed header.
+        assert_eq!(&bytes[16..24], &[0; 8]);
+
+        let mut attribute = 8u16.to_ne_bytes().to_vec();
+        attribute.extend_from_slice(&address_attribute::TARGET_NETNSID.to_ne_bytes());
+        attribute.extend_from_slice(&2i32.to_ne_bytes());
+        assert_eq!(&bytes[24..length], attribute.as_slice());
+    }
+
+    #[test]
+    fn add_address() {
+        let address: IpAddr = "2001:db8::10".parse().unwrap();
+        let message = AddressBuilder::add_address(3, address, 64).build();
+        assert_eq!(message.header.kind, libc::RTM_NEWADDR);
+        assert_eq!(
+            message.header.flags,
+            (libc::NLM_F_REQUEST | libc::NLM_F_CREATE | libc::NLM_F_EXCL) as u16
+        );
+        assert_eq!(message.header.length, 16 + 8 + 20 + 20);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        match NetlinkMessage::from(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Address(parsed)),
+                ..
+            }) => {
+                assert_eq!(parsed.message.family, family::INET6);
+                assert_eq!(parsed.message.prefix_length, 64);
+                assert_eq!(parsed.message.index, 3);
+                assert_eq!(parsed.address(), Some(address));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn add_alt_name() {
+        let message = LinkBuilder::add_alt_name(4, "uplink").build();
+        assert_eq!(message.header.kind, message_type::NEWLINKPROP);
+        assert_eq!(
+            message.header.flags,
+            (libc::NLM_F_REQUEST | libc::NLM_F_CREATE | libc::NLM_F_EXCL) as u16
+        );
+        assert_eq!(message.header.length, 16 + 16 + 16);
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.message.index, 4);
+        assert_eq!(link.alt_names(), vec!["uplink"]);
+
+        let message = LinkBuilder::del_alt_name(4, "uplink").build();
+        assert_eq!(message.header.kind, message_type::DELLINKPROP);
+    }
+
+    #[test]
+    fn create_veth() {
+        let message = LinkBuilder::create_veth("veth0", "veth1").build();
+        assert_eq!(message.header.kind, libc::RTM_NEWLINK);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.name(), Some("veth0"));
+        assert_eq!(link.info_kind(), Some("veth"));
+
+        let mut peer = None;
+        visit_attributes(
+            link.link_info(libc::IFLA_INFO_DATA).unwrap(),
+            |kind, payload| {
+                if kind == veth_attribute::PEER {
+                    peer = Some(payload);
+                }
+            },
+        )
+        .unwrap();
+        let peer = peer.unwrap();
+        assert_eq!(&peer[..16], &[0; 16]);
+        let mut name = vec![];
+        visit_attributes(&peer[16..], |kind, payload| {
+            if kind == libc::IFLA_IFNAME {
+                name = payload.to_vec();
+            }
+        })
+        .unwrap();
+        assert_eq!(name, b"veth1\0");
+
+        let message = LinkBuilder::create_dummy("dummy0").build();
+        let length = message.to_array(&mut bytes);
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.info_kind(), Some("dummy"));
+        assert!(link.link_info(libc::IFLA_INFO_DATA).is_none());
+    }
+
+    #[test]
+    fn interface_name_validation() {
+        assert!(LinkBuilder::create_dummy("fifteen-bytes-0")
+            .build()
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            LinkBuilder::create_dummy("sixteen-bytes-00").build().validate(),
+            Err(BuildError::NameTooLong { name }) if name == "sixteen-bytes-00"
+        ));
+        assert!(matches!(
+            LinkBuilder::create_veth("veth0", "sixteen-bytes-00")
+                .build()
+                .validate(),
+            Err(BuildError::NameTooLong { .. })
+        ));
+        assert!(matches!(
+            LinkBuilder::add_vlan("eth0\0.100", 2, 100)
+                .build()
+                .validate(),
+            Err(BuildError::NameContainsNul { .. })
+        ));
+        assert!(matches!(
+            LinkBuilder::create_veth("veth0", "veth\0x")
+                .build()
+                .validate(),
+            Err(BuildError::NameContainsNul { .. })
+        ));
+    }
+
+    #[test]
+    fn add_vlan() {
+        let message = LinkBuilder::add_vlan("eth0.100", 2, 100).build();
+        assert_eq!(message.header.kind, libc::RTM_NEWLINK);
+        assert_eq!(
+            message.header.flags,
+            (libc::NLM_F_REQUEST | libc::NLM_F_CREATE | libc::NLM_F_EXCL) as u16
+        );
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        let link = parse_link(&bytes[..length]);
+        assert_eq!(link.info_kind(), Some("vlan"));
+        assert_eq!(link.vlan_info().map(|info| info.id), Some(100));
+    }
+
+    #[test]
+    fn add_mpls_route() {
+        let message = RouteBuilder::add_mpls_route(100)
+            .oif(2)
+            .ttl_propagate(true)
+            .build();
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        let options = ParseOptions {
+            strict_message_type: Some(libc::RTM_NEWROUTE),
+            ..Default::default()
+        };
+        match NetlinkMessage::from_with_options(&bytes[..length], options) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Route(route)),
+                ..
+            }) => {
+                assert_eq!(route.message.family, family::MPLS);
+                assert_eq!(route.message.destination_prefix_length, 20);
+                assert_eq!(route.mpls_labels(), Some(vec![100]));
+                assert_eq!(route.oif(), Some(2));
+                assert_eq!(route.ttl_propagate(), Some(true));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn get_route_mark() {
+        let message = RouteBuilder::get_route(IpAddr::from([198, 51, 100, 1]))
+            .mark(0x10)
+            .build();
+        assert_eq!(message.header.kind, libc::RTM_GETROUTE);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 12 + 8 + 8);
+        match NetlinkMessage::from(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Route(route)),
+                ..
+            }) => {
+                assert_eq!(route.destination(), Some(IpAddr::from([198, 51, 100, 1])));
+                assert_eq!(route.mark(), Some(0x10));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn get_stats() {
+        use crate::message::route::stats_attribute;
+
+        let filter_mask = stats_attribute::filter_bit(stats_attribute::LINK_64);
+        let message = get_stats_request(2, filter_mask).build();
+        assert_eq!(message.header.kind, message_type::GETSTATS);
+        assert_eq!(message.header.length, 16 + 12);
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 12);
+        assert_eq!(&bytes[16..20], &[family::UNSPEC, 0, 0, 0]);
+        match NetlinkMessage::from(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Stats(stats)),
+                ..
+            }) => {
+                assert_eq!(stats.message.index, 2);
+                assert_eq!(stats.message.filter_mask, 1);
+                assert!(stats.link_64().is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn add_route_nexthop_id() {
+        let message = RouteBuilder::add_route(IpAddr::from([198, 51, 100, 0]), 24)
+            .nexthop_id(10)
+            .build();
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + 12 + 8 + 8);
+        match NetlinkMessage::from(&bytes[..length]) {
+            Ok(NetlinkMessage {
+                payload: NetlinkPayload::Route(MessageType::Route(route)),
+                ..
+            }) => {
+                assert_eq!(route.message.destination_prefix_length, 24);
+                assert_eq!(route.nexthop_id(), Some(10));
+            }
+            _ => unreachable!(),
+        }
+
+        let message = RouteBuilder::add_route(IpAddr::from([198, 51, 100, 0]), 24)
+            .nexthop_id(10)
+            .oif(2)
+            .build();
+        assert!(matches!(
+            message.validate(),
+            Err(BuildError::ConflictingNextHop)
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use super::{
+    route::{message_type, Address, Link, MessageType, NextHopObject, Route},
+    NetlinkMessage, NetlinkPayload,
+};
+
+/// Routing notification (e.g. from a socket subscribed to
+/// [`crate::socket::netlink_groups::ALL_ROUTE`]).
+pub enum RouteEvent {
+    /// New interface (`RTM_NEWLINK` reporting all fields changed).
+    LinkAdded(Link),
+    /// Interface state or configuration change (`RTM_NEWLINK`).
+    LinkChanged(Link),
+    /// Interface removed (`RTM_DELLINK`).
+    LinkRemoved(Link),
+    /// Address added (`RTM_NEWADDR`).
+    AddressAdded(Address),
+    /// Address removed (`RTM_DELADDR`).
+    AddressRemoved(Address),
+    /// Route added or replaced (`RTM_NEWROUTE`).
+    RouteAdded(Route),
+    /// Route removed (`RTM_DELROUTE`).
+    RouteRemoved(Route),
+    /// Nexthop object added or replaced (`RTM_NEWNEXTHOP`).
+    NextHopAdded(NextHopObject),
+    /// Nexthop object removed (`RTM_DELNEXTHOP`).
+    NextHopRemoved(NextHopObject),
+}
+
+impl RouteEvent {
+    /// Map a routing message to its event, returns `None` for other
+    /// messages (e.g. errors, requests or unparsed payloads).
+    pub fn from_message(message: NetlinkMessage) -> Option<RouteEvent> {
+        let kind = message.header.kind;
+        let NetlinkPayload::Route(payload) = message.payload else {
+            return None;
+        };
+
+        match (kind, payload) {
+            // The kernel announces new interfaces with every field marked as
+            // changed (`ifi_change` all ones).
+            (libc::RTM_NEWLINK, MessageType::Link(link)) if link.message.change == u32::MAX => {
+                Some(RouteEvent::LinkAdded(link))
+            }
+            (libc::RTM_NEWLINK, MessageType::Link(link)) => Some(RouteEvent::LinkChanged(link)),
+            (libc::RTM_DELLINK, MessageType::Link(link)) => Some(RouteEvent::LinkRemoved(link)),
+            (libc::RTM_NEWADDR, MessageType::Address(address)) => {
+                Some(RouteEvent::AddressAdded(address))
+            }
+            (libc::RTM_DELADDR, MessageType::Address(address)) => {
+                Some(RouteEvent::AddressRemoved(address))
+            }
+            (libc::RTM_NEWROUTE, MessageType::Route(route)) => Some(RouteEvent::RouteAdded(route)),
+            (libc::RTM_DELROUTE, MessageType::Route(route)) => {
+                Some(RouteEvent::RouteRemoved(route))
+            }
+            (message_type::NEWNEXTHOP, MessageType::NextHop(nexthop)) => {
+                Some(RouteEvent::NextHopAdded(nexthop))
+            }
+            (message_type::DELNEXTHOP, MessageType::NextHop(nexthop)) => {
+                Some(RouteEvent::NextHopRemoved(nexthop))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod route_event_test {
+    use super::*;
+
+    fn link_message(kind: u16, index: i32, change: u32) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&32u32.to_ne_bytes());
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&[0x00; 10]); // Flags, sequence and port ID
+        bytes.extend_from_slice(&[0x00; 4]); // Family, padding and type
+        bytes.extend_from_slice(&index.to_ne_bytes());
+        bytes.extend_from_slice(&(libc::IFF_UP as u32).to_ne_bytes());
+        bytes.extend_from_slice(&change.to_ne_bytes());
+        bytes
+    }
+
+    fn event(bytes: &[u8]) -> Option<RouteEvent> {
+        RouteEvent::from_message(NetlinkMessage::from(bytes).unwrap())
+    }
+
+    #[test]
+    fn link_events() {
+        match event(&link_message(libc::RTM_NEWLINK, 3, u32::MAX)) {
+            Some(RouteEvent::LinkAdded(link)) => assert_eq!(link.message.index, 3),
+            _ => unreachable!(),
+        }
+        match event(&link_message(libc::RTM_NEWLINK, 3, libc::IFF_UP as u32)) {
+            Some(RouteEvent::LinkChanged(link)) => assert_eq!(link.message.index, 3),
+            _ => unreachable!(),
+        }
+        match event(&link_message(libc::RTM_DELLINK, 3, 0)) {
+            Some(RouteEvent::LinkRemoved(link)) => assert_eq!(link.message.index, 3),
+            _ => unreachable!(),
+        }
+        // Requests are not events.
+        assert!(event(&link_message(libc::RTM_GETLINK, 3, 0)).is_none());
+    }
+}
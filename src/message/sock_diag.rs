@@ -0,0 +1,344 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use super::{
+    packet_writer::PacketWriter, route_attribute::*, NetlinkHeader, NetlinkMessage,
+    NetlinkParseError, NetlinkParseResult, NetlinkPayload, PacketParser, PortId,
+};
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub enum MessageType {
+    Request(InetDiagRequest),
+    Message(InetDiagMessage),
+}
+
+//
+// Constants definitions
+//
+
+/// Socket diagnostics message type (`SOCK_DIAG_BY_FAMILY`).
+pub const SOCK_DIAG_BY_FAMILY: u16 = 20;
+
+/// TCP states
+/// ([source](https://github.com/torvalds/linux/blob/v6.11/include/net/tcp_states.h#L12)).
+///
+/// Use `1 << state` to build [`InetDiagRequest::states`] masks.
+pub mod tcp_state {
+    pub const ESTABLISHED: u8 = 1;
+    pub const SYN_SENT: u8 = 2;
+    pub const SYN_RECV: u8 = 3;
+    pub const FIN_WAIT1: u8 = 4;
+    pub const FIN_WAIT2: u8 = 5;
+    pub const TIME_WAIT: u8 = 6;
+    pub const CLOSE: u8 = 7;
+    pub const CLOSE_WAIT: u8 = 8;
+    pub const LAST_ACK: u8 = 9;
+    pub const LISTEN: u8 = 10;
+    pub const CLOSING: u8 = 11;
+}
+
+//
+// Struct definitions
+//
+
+/// Socket identification (`struct inet_diag_sockid`), ports and addresses
+/// are converted from network byte order.
+pub struct InetDiagSocketId {
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub source: IpAddr,
+    pub destination: IpAddr,
+    /// Bound interface index.
+    pub interface: u32,
+    pub cookie: [u32; 2],
+}
+
+impl InetDiagSocketId {
+    fn unspecified(family: u8) -> InetDiagSocketId {
+        let address = if family == libc::AF_INET6 as u8 {
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        };
+
+        InetDiagSocketId {
+            source_port: 0,
+            destination_port: 0,
+            source: address,
+            destination: address,
+            interface: 0,
+            cookie: [0, 0],
+        }
+    }
+
+    fn read_address(parser: &mut PacketParser, family: u8) -> IpAddr {
+        let bytes: [u8; 16] = parser.read_slice(16).try_into().unwrap_or([0; 16]);
+        if family == libc::AF_INET6 as u8 {
+            IpAddr::from(bytes)
+        } else {
+            IpAddr::from([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    }
+
+    fn from(parser: &mut PacketParser, family: u8) -> InetDiagSocketId {
+        let source_port = u16::from_be(parser.read_u16());
+        let destination_port = u16::from_be(parser.read_u16());
+        InetDiagSocketId {
+            source_port,
+            destination_port,
+            source: InetDiagSocketId::read_address(parser, family),
+            destination: InetDiagSocketId::read_address(parser, family),
+            interface: parser.read_u32(),
+            cookie: [parser.read_u32(), parser.read_u32()],
+        }
+    }
+
+    fn to_array(&self, writter: &mut PacketWriter) {
+        let write_address = |writter: &mut PacketWriter, address: &IpAddr| match address {
+            IpAddr::V4(address) => {
+                writter.write_bytes(&address.octets());
+                writter.write_bytes(&[0u8; 12]);
+            }
+            IpAddr::V6(address) => writter.write_bytes(&address.octets()),
+        };
+
+        writter.write_u16(self.source_port.to_be());
+        writter.write_u16(self.destination_port.to_be());
+        write_address(writter, &self.source);
+        write_address(writter, &self.destination);
+        writter.write_u32(self.interface);
+        writter.write_u32(self.cookie[0]);
+        writter.write_u32(self.cookie[1]);
+    }
+}
+
+/// Socket dump request (`struct inet_diag_req_v2`).
+pub struct InetDiagRequest {
+    /// Address family (`AF_INET` or `AF_INET6`).
+    pub family: u8,
+    /// Transport protocol (e.g. `IPPROTO_TCP`).
+    pub protocol: u8,
+    /// Extensions to report (`1 << (INET_DIAG_* - 1)`).
+    pub extensions: u8,
+    /// Bit mask of the socket states to report, see [`tcp_state`].
+    pub states: u32,
+    pub id: InetDiagSocketId,
+}
+
+impl InetDiagRequest {
+    /// Request size on the wire.
+    pub const SIZE: usize = 56;
+
+    pub fn to_array(self, writter: &mut PacketWriter) {
+        writter.write_u8(self.family);
+        writter.write_u8(self.protocol);
+        writter.write_u8(self.extensions);
+        writter.write_u8(0);
+        writter.write_u32(self.states);
+        self.id.to_array(writter);
+    }
+}
+
+/// Socket information (`struct inet_diag_msg`).
+pub struct InetDiagMessage {
+    pub family: u8,
+    /// See [`tcp_state`] constants.
+    pub state: u8,
+    pub timer: u8,
+    pub retransmits: u8,
+    pub id: InetDiagSocketId,
+    pub expires: u32,
+    pub receive_queue: u32,
+    pub send_queue: u32,
+    pub uid: u32,
+    pub inode: u32,
+    /// Requested extensions (`INET_DIAG_*` attributes).
+    pub attributes: Vec<Attribute>,
+}
+
+impl InetDiagMessage {
+    /// Message size on the wire without attributes.
+    pub const SIZE: usize = 72;
+
+    pub fn from(parser: &mut PacketParser) -> NetlinkParseResult<InetDiagMessage> {
+        if (parser.remaining() as usize) < InetDiagMessage::SIZE {
+            return Err(NetlinkParseError::MessageIncomplete);
+        }
+
+        let family = parser.read_u8();
+        let state = parser.read_u8();
+        let timer = parser.read_u8();
+        let retransmits = parser.read_u8();
+        let id = InetDiagSocketId::from(parser, family);
+        let expires = parser.read_u32();
+        let receive_queue = parser.read_u32();
+        let send_queue = parser.read_u32();
+        let uid = parser.read_u32();
+        let inode = parser.read_u32();
+        let mut attributes = vec![];
+
+        while parser.remaining() > 0 {
+            let length = parser.read_u16();
+            let kind = parser.read_u16();
+            attributes.push(Attribute::Unknown(AttributeValue::<Vec<u8>>::from(
+                parser, length, kind,
+            )?));
+        }
+
+        Ok(InetDiagMessage {
+            family,
+            state,
+            timer,
+            retransmits,
+            id,
+            expires,
+            receive_queue,
+            send_queue,
+            uid,
+            inode,
+            attributes,
+        })
+    }
+}
+
+/// Socket dump request (`SOCK_DIAG_BY_FAMILY`) builder.
+pub struct InetDiagBuilder {
+    request: InetDiagRequest,
+}
+
+impl InetDiagBuilder {
+    /// Dump all `protocol` (e.g. `IPPROTO_TCP`) sockets of address family
+    /// `family` (`AF_INET` or `AF_INET6`) in any state.
+    pub fn dump(family: u8, protocol: u8) -> InetDiagBuilder {
+        InetDiagBuilder {
+            request: InetDiagRequest {
+                family,
+                protocol,
+                extensions: 0,
+                states: u32::MAX,
+                id: InetDiagSocketId::unspecified(family),
+            },
+        }
+    }
+
+    /// Only report sockets in `states` (bit mask of `1 << state`, see
+    /// [`tcp_state`]).
+    pub fn states(mut self, states: u32) -> InetDiagBuilder {
+        self.request.states = states;
+        self
+    }
+
+    /// Create the message with sequence zero: the sequence is assigned
+    /// when sending through [`crate::connection::NetlinkConnection`].
+    pub fn build(self) -> NetlinkMessage<'static> {
+        NetlinkMessage::new(
+            NetlinkHeader {
+                length: (mem::size_of::<NetlinkHeader>() + InetDiagRequest::SIZE) as u32,
+                kind: SOCK_DIAG_BY_FAMILY,
+                flags: (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16,
+                sequence: 0,
+                port_id: PortId::kernel(),
+            },
+            NetlinkPayload::SockDiag(MessageType::Request(self.request)),
+        )
+    }
+
+    /// Create the message with the next sequence number of `connection`
+    /// and serialize it (see
+    /// [`crate::connection::NetlinkConnection::serialize`]).
+    #[cfg(feature = "socket")]
+    pub fn build_for<T: crate::socket::Transport>(
+        self,
+        connection: &mut crate::connection::NetlinkConnection<T>,
+    ) -> crate::Result<(Vec<u8>, u32)> {
+        connection.serialize(self.build())
+    }
+}
+
+#[cfg(test)]
+mod sock_diag_test {
+    use super::*;
+    #[cfg(feature = "socket")]
+    use crate::{connection::NetlinkConnection, protocol::NetlinkProtocol};
+    #[cfg(feature = "socket")]
+    use std::net::TcpListener;
+
+    #[test]
+    fn dump_request() {
+        let message = InetDiagBuilder::dump(libc::AF_INET as u8, libc::IPPROTO_TCP as u8)
+            .states(1 << tcp_state::LISTEN)
+            .build();
+        assert!(message.validate().is_ok());
+
+        let mut bytes = [0u8; 128];
+        let length = message.to_array(&mut bytes);
+        assert_eq!(length, 16 + InetDiagRequest::SIZE);
+        assert_eq!(
+            &bytes[16..24],
+            &[
+                libc::AF_INET as u8,
+                libc::IPPROTO_TCP as u8,
+                0,
+                0,
+                0x00,
+                0x04,
+                0x00,
+                0x00
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "socket")]
+    fn listening_tcp_sockets() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut sockets = vec![];
+        let mut connection = NetlinkConnection::new(NetlinkProtocol::SockDiag).unwrap();
+        connection
+            .request(
+                InetDiagBuilder::dump(libc::AF_INET as u8, libc::IPPROTO_TCP as u8)
+                    .states(1 << tcp_state::LISTEN)
+                    .build(),
+                |message| {
+                    if let NetlinkPayload::SockDiag(MessageType::Message(socket)) = message.payload
+                    {
+                        sockets.push(socket);
+                    }
+                },
+            )
+            .unwrap();
+
+        let socket = sockets
+            .iter()
+            .find(|socket| socket.id.source_port == port)
+            .unwrap();
+        assert_eq!(socket.family, libc::AF_INET as u8);
+        assert_eq!(socket.state, tcp_state::LISTEN);
+        assert_eq!(socket.id.source, IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(socket.uid, unsafe { libc::getuid() });
+        assert_ne!(socket.inode, 0);
+    }
+}
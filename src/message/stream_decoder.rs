@@ -0,0 +1,140 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use super::{NetlinkHeader, NetlinkMessage, NetlinkParseError, NetlinkParseResult};
+use std::mem;
+
+/// Netlink messages decoder for byte streams.
+///
+/// Netlink sockets are message framed, but relayed transports (pipes, TCP
+/// etc...) may deliver partial messages. The decoder accumulates the bytes
+/// and only returns messages after they are complete.
+#[derive(Default)]
+pub struct StreamDecoder {
+    buffer: Vec<u8>,
+    /// Bytes of the buffer already returned as messages.
+    consumed: usize,
+    /// Alignment padding of the last message not yet received.
+    padding: usize,
+}
+
+impl StreamDecoder {
+    pub fn new() -> StreamDecoder {
+        StreamDecoder::default()
+    }
+
+    fn compact(&mut self) {
+        self.buffer.drain(..self.consumed);
+        self.consumed = 0;
+
+        let padding = self.padding.min(self.buffer.len());
+        self.buffer.drain(..padding);
+        self.padding -= padding;
+    }
+
+    /// Append received bytes to the decoder.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.compact();
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Amount of bytes buffered waiting for the message to complete.
+    pub fn pending(&self) -> usize {
+        self.buffer.len() - self.consumed
+    }
+
+    /// Return the next complete message or `None` if more bytes are needed.
+    ///
+    /// A message with an invalid length makes the decoder discard all
+    /// buffered bytes, since the next message start can't be found.
+    pub fn decode(&mut self) -> Option<NetlinkParseResult<NetlinkMessage<'_>>> {
+        self.compact();
+        if self.buffer.len() < mem::size_of::<NetlinkHeader>() {
+            return None;
+        }
+
+        let length = u32::from_ne_bytes([
+            self.buffer[0],
+            self.buffer[1],
+            self.buffer[2],
+            self.buffer[3],
+        ]) as usize;
+        if length < mem::size_of::<NetlinkHeader>() {
+            self.consumed = self.buffer.len();
+            return Some(Err(NetlinkParseError::MessageTooSmall));
+        }
+        if self.buffer.len() < length {
+            return None;
+        }
+
+        let aligned_length = length.next_multiple_of(4);
+        self.consumed = aligned_length.min(self.buffer.len());
+        self.padding = aligned_length - self.consumed;
+        Some(NetlinkMessage::from(&self.buffer[..length]))
+    }
+}
+
+#[cfg(test)]
+mod stream_decoder_test {
+    use super::*;
+    use crate::message::NetlinkPayload;
+
+    fn message(sequence: u32) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&20u32.to_ne_bytes()); // Length
+        bytes.extend_from_slice(&libc::RTM_NEWNEIGH.to_ne_bytes()); // Type
+        bytes.extend_from_slice(&0u16.to_ne_bytes()); // Flags
+        bytes.extend_from_slice(&sequence.to_ne_bytes()); // Sequence
+        bytes.extend_from_slice(&0u32.to_ne_bytes()); // Port ID
+        bytes.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]); // Payload
+        bytes
+    }
+
+    #[test]
+    fn byte_by_byte() {
+        let mut bytes = message(1);
+        bytes.extend(message(2));
+        let mut decoder = StreamDecoder::new();
+        let mut sequences = vec![];
+
+        for (index, byte) in bytes.iter().enumerate() {
+            decoder.push(&[*byte]);
+            match decoder.decode() {
+                Some(Ok(message)) => {
+                    assert_eq!(index + 1, 20 * message.header.sequence as usize);
+                    assert!(matches!(
+                        message.payload,
+                        NetlinkPayload::Unknown(&[0x01, 0x02, 0x03, 0x04])
+                    ));
+                    sequences.push(message.header.sequence);
+                }
+                Some(Err(error)) => panic!("{}", error),
+                None => (),
+            }
+        }
+
+        assert_eq!(sequences, vec![1, 2]);
+        assert!(decoder.decode().is_none());
+        assert_eq!(decoder.pending(), 0);
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::connection::NetlinkConnection;
+use crate::message::route::MessageType;
+use crate::message::route_builder::get_nsid_request;
+use crate::message::NetlinkPayload;
+use crate::socket::NetlinkSocket;
+use crate::Result;
+use std::io::{Error, ErrorKind};
+use std::os::fd::RawFd;
+
+/// Get the ID assigned by the kernel (from the point of view of the
+/// `socket` namespace) to the namespace referenced by `ns_fd` (e.g. an
+/// open `/var/run/netns/<name>` file).
+///
+/// Returns [`crate::message::route::NSID_NOT_ASSIGNED`] when the namespace
+/// has no ID yet.
+pub fn get_nsid(socket: &NetlinkSocket, ns_fd: RawFd) -> Result<i32> {
+    let mut nsid = None;
+    NetlinkConnection::from_transport(socket).request(
+        get_nsid_request().fd(ns_fd).build(),
+        |message| {
+            if let NetlinkPayload::Route(MessageType::NsId(reply)) = message.payload {
+                nsid = reply.nsid();
+            }
+        },
+    )?;
+
+    nsid.ok_or_else(|| Error::new(ErrorKind::InvalidData, "namespace ID missing in reply").into())
+}
+
+#[cfg(test)]
+mod namespace_test {
+    use super::*;
+    use crate::message::route::NSID_NOT_ASSIGNED;
+    use crate::message::PortId;
+    use crate::socket::NetlinkProtocol;
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+
+    #[test]
+    fn current_nsid() {
+        let created = std::thread::spawn(|| {
+            // Nothing assigns IDs in a fresh namespace.
+            if unsafe { libc::unshare(libc::CLONE_NEWNET) } == -1 {
+                return false;
+            }
+
+            let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+            let netns = File::open("/proc/thread-self/ns/net").unwrap();
+            assert_eq!(
+                get_nsid(&socket, netns.as_raw_fd()).unwrap(),
+                NSID_NOT_ASSIGNED
+            );
+            true
+        })
+        .join()
+        .unwrap();
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Netlink protocols enumeration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetlinkProtocol {
+    /// Netlink routing messages: interfaces, addresses, routes etc...
+    Route = libc::NETLINK_ROUTE as isize,
+    /// Socket monitoring (TCP, UDP, UNIX etc...).
+    SockDiag = libc::NETLINK_SOCK_DIAG as isize,
+    /// IPsec transformations and policies.
+    Xfrm = libc::NETLINK_XFRM as isize,
+    /// Kernel audit subsystem.
+    Audit = libc::NETLINK_AUDIT as isize,
+    /// Netfilter subsystems: connection tracking, queueing, logging etc...
+    Netfilter = libc::NETLINK_NETFILTER as isize,
+    /// Kernel device events (udev).
+    KobjectUevent = libc::NETLINK_KOBJECT_UEVENT as isize,
+    /// Generic netlink: dynamically registered families.
+    Generic = libc::NETLINK_GENERIC as isize,
+    /// Reserved for user mode socket protocols.
+    UserSock = libc::NETLINK_USERSOCK as isize,
+}
+
+impl NetlinkProtocol {
+    fn name(&self) -> &'static str {
+        match self {
+            NetlinkProtocol::Route => "route",
+            NetlinkProtocol::SockDiag => "sock_diag",
+            NetlinkProtocol::Xfrm => "xfrm",
+            NetlinkProtocol::Audit => "audit",
+            NetlinkProtocol::Netfilter => "netfilter",
+            NetlinkProtocol::KobjectUevent => "kobject_uevent",
+            NetlinkProtocol::Generic => "generic",
+            NetlinkProtocol::UserSock => "usersock",
+        }
+    }
+}
+
+impl fmt::Display for NetlinkProtocol {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+/// Unknown protocol name passed to [`NetlinkProtocol::from_str`].
+#[derive(Debug)]
+pub struct ParseNetlinkProtocolError(String);
+
+impl fmt::Display for ParseNetlinkProtocolError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "unknown netlink protocol \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseNetlinkProtocolError {}
+
+impl FromStr for NetlinkProtocol {
+    type Err = ParseNetlinkProtocolError;
+
+    /// Parse the protocol name (case insensitive) as printed by
+    /// [`NetlinkProtocol`]'s `Display` implementation.
+    fn from_str(name: &str) -> std::result::Result<NetlinkProtocol, Self::Err> {
+        [
+            NetlinkProtocol::Route,
+            NetlinkProtocol::SockDiag,
+            NetlinkProtocol::Xfrm,
+            NetlinkProtocol::Audit,
+            NetlinkProtocol::Netfilter,
+            NetlinkProtocol::KobjectUevent,
+            NetlinkProtocol::Generic,
+            NetlinkProtocol::UserSock,
+        ]
+        .into_iter()
+        .find(|protocol| protocol.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| ParseNetlinkProtocolError(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod protocol_test {
+    use super::*;
+
+    #[test]
+    fn protocol_from_str() {
+        assert_eq!(
+            "route".parse::<NetlinkProtocol>().unwrap(),
+            NetlinkProtocol::Route
+        );
+        assert_eq!(
+            "NetFilter".parse::<NetlinkProtocol>().unwrap(),
+            NetlinkProtocol::Netfilter
+        );
+        assert_eq!(NetlinkProtocol::Generic.to_string(), "generic");
+
+        let error = "bogus".parse::<NetlinkProtocol>().unwrap_err();
+        assert_eq!(error.to_string(), "unknown netlink protocol \"bogus\"");
+    }
+}
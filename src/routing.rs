@@ -0,0 +1,105 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+use crate::connection::NetlinkConnection;
+use crate::message::route::{MessageType, Route};
+use crate::message::route_builder::get_routes_request;
+use crate::message::NetlinkPayload;
+use crate::socket::NetlinkProtocol;
+use crate::Result;
+
+/// Delete all routes of routing table `table` (`ip route flush table X`),
+/// returns the number of routes deleted.
+///
+/// Routes removed by someone else during the flush (`ESRCH` or `ENOENT`)
+/// are skipped and not counted.
+pub fn flush_routes(table: u32) -> Result<usize> {
+    let mut routes: Vec<Route> = vec![];
+    let mut connection = NetlinkConnection::new(NetlinkProtocol::Route)?;
+    connection.request(get_routes_request().build(), |message| {
+        if let NetlinkPayload::Route(MessageType::Route(route)) = message.payload {
+            if route.table() == table {
+                routes.push(route);
+            }
+        }
+    })?;
+
+    let mut deleted = 0;
+    for route in &routes {
+        match connection.execute(route.to_delete_request(0)) {
+            Ok(()) => deleted += 1,
+            Err(error) if matches!(error.raw_os_error(), Some(libc::ESRCH | libc::ENOENT)) => (),
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod routing_test {
+    use super::*;
+    use crate::interface::set_interface_up;
+    use crate::message::route_builder::RouteBuilder;
+    use std::net::IpAddr;
+
+    #[test]
+    fn flush_table() {
+        let created = std::thread::spawn(|| {
+            if unsafe { libc::unshare(libc::CLONE_NEWNET) } == -1 {
+                return false;
+            }
+
+            // Loopback is index 1 and starts down in new namespaces.
+            set_interface_up(1).unwrap();
+            let mut connection = NetlinkConnection::new(NetlinkProtocol::Route).unwrap();
+            for destination in [[198, 51, 100, 0], [203, 0, 113, 0]] {
+                connection
+                    .execute(
+                        RouteBuilder::add_route(IpAddr::from(destination), 24)
+                            .table(1000)
+                            .oif(1)
+                            .build(),
+                    )
+                    .unwrap();
+            }
+            connection
+                .execute(
+                    RouteBuilder::add_route(IpAddr::from([192, 0, 2, 0]), 24)
+                        .table(1001)
+                        .oif(1)
+                        .build(),
+                )
+                .unwrap();
+
+            assert_eq!(flush_routes(1000).unwrap(), 2);
+            assert_eq!(flush_routes(1000).unwrap(), 0);
+            assert_eq!(flush_routes(1001).unwrap(), 1);
+            true
+        })
+        .join()
+        .unwrap();
+        if !created {
+            eprintln!("skipping: unable to create network namespace");
+        }
+    }
+}
@@ -21,23 +21,151 @@
 // OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
 // SUCH DAMAGE.
 
+use crate::message::{NetlinkMessageIterator, PortId};
+pub use crate::protocol::{NetlinkProtocol, ParseNetlinkProtocolError};
+use std::fmt;
+use std::fs::File;
 use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
 use std::mem;
+use std::os::fd::{AsRawFd, RawFd};
+use std::time::Duration;
 
 /// Netlink socket structure.
 ///
 /// Wrapper on the file descriptor created by `socket()` system call.
 pub struct NetlinkSocket {
     descriptor: i32,
+    protocol: NetlinkProtocol,
+    trace: Option<TraceCallback>,
 }
 
-/// Netlink protocols enumeration.
-pub enum NetlinkProtocol {
-    /// Netlink routing messages: interfaces, addresses, routes etc...
-    Route = libc::NETLINK_ROUTE as isize,
+/// Netlink socket errors, reported inside [`std::io::Error`] (see
+/// [`std::io::Error::get_ref`]).
+#[derive(Debug)]
+pub enum NetlinkError {
+    /// Subscribing to multicast `groups` requires privileges (usually
+    /// `CAP_NET_ADMIN`).
+    PermissionDenied { groups: u32 },
+    /// Messages of protocol `expected` requested from a socket bound with
+    /// protocol `bound`.
+    ProtocolMismatch {
+        expected: NetlinkProtocol,
+        bound: NetlinkProtocol,
+    },
+    /// The socket receive buffer overflowed (`ENOBUFS`) and messages were
+    /// dropped, see [`NetlinkSocket::recv`].
+    Overrun,
 }
 
+impl fmt::Display for NetlinkError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetlinkError::PermissionDenied { groups } => write!(
+                formatter,
+                "permission denied subscribing to netlink groups {:#x}",
+                groups
+            ),
+            NetlinkError::ProtocolMismatch { expected, bound } => write!(
+                formatter,
+                "expected {} netlink messages, but socket is bound to {}",
+                expected, bound
+            ),
+            NetlinkError::Overrun => {
+                formatter.write_str("netlink receive buffer overrun, messages lost")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetlinkError {}
+
+/// Last receive error, with `ENOBUFS` reported as [`NetlinkError::Overrun`].
+fn recv_error() -> Error {
+    let error = Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::ENOBUFS) => Error::other(NetlinkError::Overrun),
+        _ => error,
+    }
+}
+
+/// Socket buffer size option value, sizes above `c_int` are rejected
+/// instead of being truncated.
+fn buffer_size(bytes: usize) -> Result<libc::c_int> {
+    libc::c_int::try_from(bytes)
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "buffer size too large"))
+}
+
+/// Netlink socket types.
+///
+/// Netlink doesn't distinguish them: both exchange whole datagrams and
+/// behave the same, the choice only matters to tools inspecting the socket
+/// (e.g. `ss` or `/proc/net/netlink`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SocketType {
+    /// `SOCK_DGRAM`
+    #[default]
+    Datagram,
+    /// `SOCK_RAW`
+    Raw,
+}
+
+/// Socket creation options for [`NetlinkSocket::bind_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct BindOptions {
+    /// Close the socket on `exec()` (`SOCK_CLOEXEC`), disable to pass the
+    /// socket to child processes.
+    pub cloexec: bool,
+    /// Non blocking socket (`SOCK_NONBLOCK`).
+    pub nonblocking: bool,
+    pub socket_type: SocketType,
+}
+
+impl Default for BindOptions {
+    fn default() -> BindOptions {
+        BindOptions {
+            cloexec: true,
+            nonblocking: false,
+            socket_type: SocketType::Datagram,
+        }
+    }
+}
+
+/// Netlink socket address (`struct sockaddr_nl`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SocketAddress {
+    /// Port ID: [`PortId::kernel`] for the kernel.
+    pub pid: PortId,
+    /// Multicast groups bit mask: when receiving it contains the bit of the
+    /// group the message was sent to (zero for unicast).
+    pub groups: u32,
+    /// Multicast group number the message was sent to, only reported by
+    /// [`NetlinkSocket::recv_from`] with [`NetlinkSocket::set_pktinfo`]
+    /// enabled (zero otherwise and for unicast).
+    pub group: u32,
+}
+
+impl SocketAddress {
+    fn to_sockaddr(self) -> libc::sockaddr_nl {
+        let mut socket_address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        socket_address.nl_family = libc::AF_NETLINK as u16;
+        socket_address.nl_pid = self.pid.0;
+        socket_address.nl_groups = self.groups;
+        socket_address
+    }
+}
+
+/// Traffic direction reported to the trace callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Send,
+    Receive,
+}
+
+/// Callback receiving every buffer sent or received by a socket.
+pub type TraceCallback = Box<dyn Fn(Direction, &[u8]) + Send + Sync>;
+
 pub mod netlink_groups {
     pub const LINK: u32 = (1 << (libc::RTNLGRP_LINK - 1)) as u32;
     pub const NOTIFY: u32 = (1 << (libc::RTNLGRP_NOTIFY - 1)) as u32;
@@ -61,49 +189,86 @@ pub mod netlink_groups {
     pub const IPV4_MROUTE_R: u32 = (1 << (libc::RTNLGRP_IPV4_MROUTE_R - 1)) as u32;
     pub const IPV6_MROUTE_R: u32 = (1 << (libc::RTNLGRP_IPV6_MROUTE_R - 1)) as u32;
     pub const NEXTHOP: u32 = (1 << (libc::RTNLGRP_NEXTHOP - 1)) as u32;
+
+    /// Interface, address, route, neighbor, rule and nexthop notifications.
+    pub const ALL_ROUTE: u32 = LINK
+        | NEIGHBOR
+        | IPV4_INTERFACE_ADDRESS
+        | IPV4_ROUTE
+        | IPV4_RULE
+        | IPV6_INTERFACE_ADDRESS
+        | IPV6_ROUTE
+        | IPV6_RULE
+        | NEXTHOP;
 }
 
 impl NetlinkSocket {
     /// Create a new socket for protocol `protocol`, bind it to the
-    /// port ID ``pid`` and subscribe to notifications groups `groups`.
+    /// port ID `pid` and subscribe to notifications groups `groups`.
     ///
-    /// `pid` is usually the process PID or something of common knowledge
-    /// between other software.
+    /// `pid` is usually [`PortId::auto`] (kernel assigned) or something of
+    /// common knowledge between other software.
     ///
-    /// `groups` is defined per `protocol` and is a bitfield.
+    /// `groups` is defined per `protocol` and is a bitfield. Subscribing to
+    /// privileged groups without permission fails with
+    /// [`NetlinkError::PermissionDenied`].
     ///
     /// Example:
     /// ```
+    /// use netlink_rs::message::PortId;
     /// use netlink_rs::socket::NetlinkProtocol;
     /// use netlink_rs::socket::NetlinkSocket;
     /// use netlink_rs::socket::netlink_groups;
     ///
     /// match NetlinkSocket::bind(
     ///     NetlinkProtocol::Route,
-    ///     0,
+    ///     PortId::auto(),
     ///     netlink_groups::LINK | netlink_groups::NEIGHBOR,
     /// ) {
     ///     Ok(_socket) => assert!(true),
     ///     Err(_error) => assert!(false),
     /// }
     /// ```
-    pub fn bind(protocol: NetlinkProtocol, pid: u32, groups: u32) -> Result<NetlinkSocket> {
-        let descriptor = unsafe {
-            libc::socket(
-                libc::AF_NETLINK,
-                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
-                protocol as i32,
-            )
+    pub fn bind(protocol: NetlinkProtocol, pid: PortId, groups: u32) -> Result<NetlinkSocket> {
+        NetlinkSocket::bind_with_options(protocol, pid, groups, BindOptions::default())
+    }
+
+    /// Same as [`NetlinkSocket::bind`], but with custom socket creation
+    /// `options` ([`NetlinkSocket::bind`] uses [`BindOptions::default`]).
+    pub fn bind_with_options(
+        protocol: NetlinkProtocol,
+        pid: PortId,
+        groups: u32,
+        options: BindOptions,
+    ) -> Result<NetlinkSocket> {
+        let mut kind = match options.socket_type {
+            SocketType::Datagram => libc::SOCK_DGRAM,
+            SocketType::Raw => libc::SOCK_RAW,
         };
+        if options.cloexec {
+            kind |= libc::SOCK_CLOEXEC;
+        }
+        if options.nonblocking {
+            kind |= libc::SOCK_NONBLOCK;
+        }
+
+        let descriptor = unsafe { libc::socket(libc::AF_NETLINK, kind, protocol as i32) };
         if descriptor == -1 {
             return Err(Error::last_os_error());
         }
+        // Close the descriptor on failure.
+        let socket = NetlinkSocket {
+            descriptor,
+            protocol,
+            trace: None,
+        };
 
-        let mut socket_address: libc::sockaddr_nl = unsafe { mem::zeroed() };
-        socket_address.nl_family = libc::AF_NETLINK as u16;
-        socket_address.nl_pid = pid;
-        socket_address.nl_groups = groups;
-
+        let mut socket_address = SocketAddress {
+            pid,
+            groups,
+            ..Default::default()
+        }
+        .to_sockaddr();
         let result = unsafe {
             libc::bind(
                 descriptor,
@@ -111,18 +276,129 @@ impl NetlinkSocket {
                 mem::size_of_val(&socket_address) as libc::socklen_t,
             )
         };
+        if result == -1 {
+            let error = Error::last_os_error();
+            return Err(match error.raw_os_error() {
+                Some(libc::EPERM | libc::EACCES) if groups != 0 => Error::new(
+                    ErrorKind::PermissionDenied,
+                    NetlinkError::PermissionDenied { groups },
+                ),
+                _ => error,
+            });
+        }
+
+        Ok(socket)
+    }
+
+    /// Same as [`NetlinkSocket::bind`], but `groups` is a list of group
+    /// numbers (e.g. `libc::RTNLGRP_LINK`) instead of a bitfield.
+    ///
+    /// Prefer this over [`NetlinkSocket::bind`] when using the group
+    /// numbers: passing a group number as bitfield silently subscribes to
+    /// the wrong groups. Groups above 32 don't fit the bind bitfield and
+    /// are joined after binding with [`NetlinkSocket::add_membership`].
+    /// Group number zero (no group) fails with `InvalidInput`.
+    pub fn bind_groups(
+        protocol: NetlinkProtocol,
+        pid: PortId,
+        groups: &[u32],
+    ) -> Result<NetlinkSocket> {
+        if groups.contains(&0) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "netlink group numbers start at one",
+            ));
+        }
+
+        let bitfield = groups
+            .iter()
+            .filter(|&&group| (1..=32).contains(&group))
+            .fold(0, |bitfield, group| bitfield | (1 << (group - 1)));
+
+        let socket = NetlinkSocket::bind(protocol, pid, bitfield)?;
+        for &group in groups.iter().filter(|&&group| group > 32) {
+            socket.add_membership(group)?;
+        }
+
+        Ok(socket)
+    }
+
+    /// Create a new socket inside the network namespace referenced by
+    /// `netns_fd` (e.g. an open `/var/run/netns/<name>` file). See
+    /// [`NetlinkSocket::bind`] for the other parameters.
+    ///
+    /// The calling thread temporarily switches to the target namespace with
+    /// `setns()` and switches back before returning. Namespaces are a
+    /// per-thread attribute, so other threads are not affected, but
+    /// anything the calling thread does concurrently (e.g. signal handlers)
+    /// runs in the target namespace. The socket stays attached to the
+    /// namespace it was created in after the switch back.
+    ///
+    /// If switching back fails the new socket is closed and the calling
+    /// thread is left in the target namespace: the error says so, and the
+    /// thread should not be used for namespace dependent work anymore (e.g.
+    /// exit it, or [`std::process::abort`] when the main thread is
+    /// affected).
+    ///
+    /// Requires `CAP_SYS_ADMIN`.
+    pub fn bind_in_netns(
+        netns_fd: RawFd,
+        protocol: NetlinkProtocol,
+        pid: PortId,
+        groups: u32,
+    ) -> Result<NetlinkSocket> {
+        let original_netns = File::open("/proc/thread-self/ns/net")?;
+        if unsafe { libc::setns(netns_fd, libc::CLONE_NEWNET) } == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        let socket = NetlinkSocket::bind(protocol, pid, groups);
+
+        if unsafe { libc::setns(original_netns.as_raw_fd(), libc::CLONE_NEWNET) } == -1 {
+            let error = Error::last_os_error();
+            return Err(Error::new(
+                error.kind(),
+                format!(
+                    "thread left in the target network namespace, unable to switch back: {}",
+                    error
+                ),
+            ));
+        }
+
+        socket
+    }
+
+    /// Port ID of the socket, assigned by the kernel when bound with
+    /// [`PortId::auto`].
+    pub fn port_id(&self) -> Result<PortId> {
+        let mut socket_address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        let mut length = mem::size_of_val(&socket_address) as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockname(
+                self.descriptor,
+                &mut socket_address as *mut libc::sockaddr_nl as *mut libc::sockaddr,
+                &mut length,
+            )
+        };
         if result == -1 {
             return Err(Error::last_os_error());
         }
 
-        Ok(NetlinkSocket { descriptor })
+        Ok(PortId(socket_address.nl_pid))
     }
 
     /// Read data from the netlink socket into array.
     ///
     /// To avoid message truncation use the constant
     /// [`crate::message::NETLINK_MESSAGE_MAXIMUM_SIZE`] for the array size.
-    pub fn recv(self, buffer: &mut [u8], flags: i32) -> Result<isize> {
+    ///
+    /// When notifications arrive faster than they are read the kernel drops
+    /// them and the next call fails with [`NetlinkError::Overrun`]: the
+    /// notifications can't be recovered, so discard the cached state and
+    /// dump it again (e.g. with
+    /// [`crate::message::route_builder::get_links_request`]) while keeping
+    /// the socket subscribed.
+    pub fn recv(&self, buffer: &mut [u8], flags: i32) -> Result<isize> {
         let bytes_read = unsafe {
             libc::recv(
                 self.descriptor,
@@ -132,14 +408,436 @@ impl NetlinkSocket {
             )
         };
         if bytes_read == -1 {
-            return Err(Error::last_os_error());
+            return Err(recv_error());
         }
         if bytes_read == 0 {
             return Err(Error::other("connection closed or buffer length zero"));
         }
 
+        if let Some(trace) = &self.trace {
+            trace(Direction::Receive, &buffer[..bytes_read as usize]);
+        }
+
         Ok(bytes_read)
     }
+
+    /// Receive one datagram and return exactly its bytes, for relaying
+    /// messages without parsing them.
+    ///
+    /// The datagram size is peeked first (`MSG_PEEK | MSG_TRUNC`), so
+    /// datagrams of any size are returned whole instead of truncated to
+    /// the buffer length like [`NetlinkSocket::recv`] does.
+    pub fn recv_raw(&self) -> Result<Vec<u8>> {
+        let length = unsafe {
+            libc::recv(
+                self.descriptor,
+                std::ptr::null_mut(),
+                0,
+                libc::MSG_PEEK | libc::MSG_TRUNC,
+            )
+        };
+        if length == -1 {
+            return Err(recv_error());
+        }
+
+        let mut buffer = vec![0u8; length as usize];
+        let bytes_read = self.recv(&mut buffer, 0)?;
+        buffer.truncate(bytes_read as usize);
+        Ok(buffer)
+    }
+
+    /// Protocol the socket was bound with.
+    pub fn protocol(&self) -> NetlinkProtocol {
+        self.protocol
+    }
+
+    /// Receive one datagram into `buffer` and iterate over its messages
+    /// with the parser of the socket protocol (see
+    /// [`crate::message::NetlinkMessage::from_protocol`]).
+    pub fn recv_message<'a>(&self, buffer: &'a mut [u8]) -> Result<NetlinkMessageIterator<'a>> {
+        let bytes_read = self.recv(buffer, 0)? as usize;
+        let buffer: &'a [u8] = buffer;
+        Ok(NetlinkMessageIterator::with_protocol(
+            &buffer[..bytes_read],
+            self.protocol,
+        ))
+    }
+
+    /// Same as [`NetlinkSocket::recv_message`], but fails with
+    /// [`NetlinkError::ProtocolMismatch`] before reading when the socket
+    /// is not a generic netlink socket.
+    pub fn recv_generic<'a>(&self, buffer: &'a mut [u8]) -> Result<NetlinkMessageIterator<'a>> {
+        if self.protocol != NetlinkProtocol::Generic {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                NetlinkError::ProtocolMismatch {
+                    expected: NetlinkProtocol::Generic,
+                    bound: self.protocol,
+                },
+            ));
+        }
+
+        self.recv_message(buffer)
+    }
+
+    /// Same as [`NetlinkSocket::recv`], but also returns the sender
+    /// address.
+    ///
+    /// Sockets subscribed to multiple multicast groups can use
+    /// [`SocketAddress::groups`] to tell which group a notification came
+    /// from (e.g. [`netlink_groups::LINK`] or [`netlink_groups::IPV4_ROUTE`]).
+    /// The bit mask can only represent the first 32 groups: groups joined
+    /// with [`NetlinkSocket::add_membership`] above that are reported as
+    /// zero, enable [`NetlinkSocket::set_pktinfo`] to get the group number
+    /// in [`SocketAddress::group`] instead.
+    pub fn recv_from(&self, buffer: &mut [u8], flags: i32) -> Result<(isize, SocketAddress)> {
+        let mut socket_address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        let mut iovec = libc::iovec {
+            iov_base: buffer.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+        // Room for `struct nl_pktinfo` (a single `u32`), aligned for the
+        // control message header.
+        let mut control = [0 as libc::c_ulong; 4];
+        let mut message: libc::msghdr = unsafe { mem::zeroed() };
+        message.msg_name = &mut socket_address as *mut libc::sockaddr_nl as *mut libc::c_void;
+        message.msg_namelen = mem::size_of_val(&socket_address) as libc::socklen_t;
+        message.msg_iov = &mut iovec;
+        message.msg_iovlen = 1;
+        message.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        message.msg_controllen = mem::size_of_val(&control);
+
+        let bytes_read = unsafe { libc::recvmsg(self.descriptor, &mut message, flags) };
+        if bytes_read == -1 {
+            return Err(recv_error());
+        }
+        if bytes_read == 0 {
+            return Err(Error::other("connection closed or buffer length zero"));
+        }
+
+        let mut group = 0;
+        let mut header = unsafe { libc::CMSG_FIRSTHDR(&message) };
+        while !header.is_null() {
+            let cmsg = unsafe { &*header };
+            if cmsg.cmsg_level == libc::SOL_NETLINK && cmsg.cmsg_type == libc::NETLINK_PKTINFO {
+                group = unsafe { (libc::CMSG_DATA(header) as *const u32).read_unaligned() };
+            }
+            header = unsafe { libc::CMSG_NXTHDR(&message, header) };
+        }
+
+        if let Some(trace) = &self.trace {
+            trace(Direction::Receive, &buffer[..bytes_read as usize]);
+        }
+
+        Ok((
+            bytes_read,
+            SocketAddress {
+                pid: PortId(socket_address.nl_pid),
+                groups: socket_address.nl_groups,
+                group,
+            },
+        ))
+    }
+
+    /// Send data from array to the kernel through the netlink socket.
+    pub fn send(&self, buffer: &[u8], flags: i32) -> Result<isize> {
+        let bytes_sent = unsafe {
+            libc::send(
+                self.descriptor,
+                buffer.as_ptr() as *const libc::c_void,
+                buffer.len(),
+                flags,
+            )
+        };
+        if bytes_sent == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        if let Some(trace) = &self.trace {
+            trace(Direction::Send, &buffer[..bytes_sent as usize]);
+        }
+
+        Ok(bytes_sent)
+    }
+
+    /// Send data from array to `address` (e.g. another process or a
+    /// multicast group).
+    ///
+    /// Sending to multicast groups usually requires `CAP_NET_ADMIN`.
+    pub fn send_to(&self, buffer: &[u8], flags: i32, address: &SocketAddress) -> Result<isize> {
+        let socket_address = address.to_sockaddr();
+        let bytes_sent = unsafe {
+            libc::sendto(
+                self.descriptor,
+                buffer.as_ptr() as *const libc::c_void,
+                buffer.len(),
+                flags,
+                &socket_address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of_val(&socket_address) as libc::socklen_t,
+            )
+        };
+        if bytes_sent == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        if let Some(trace) = &self.trace {
+            trace(Direction::Send, &buffer[..bytes_sent as usize]);
+        }
+
+        Ok(bytes_sent)
+    }
+
+    /// Install (or remove with `None`) a callback called with the raw bytes
+    /// of every successful [`NetlinkSocket::send`],
+    /// [`NetlinkSocket::send_to`], [`NetlinkSocket::recv`] and
+    /// [`NetlinkSocket::recv_from`].
+    ///
+    /// This allows plugging any logging or tracing library without adding
+    /// dependencies to this crate.
+    pub fn set_trace(&mut self, trace: Option<TraceCallback>) {
+        self.trace = trace;
+    }
+
+    /// Subscribe to notifications group number `group`
+    /// (`NETLINK_ADD_MEMBERSHIP`), the only way to join groups above 32.
+    pub fn add_membership(&self, group: u32) -> Result<()> {
+        self.set_option(
+            libc::SOL_NETLINK,
+            libc::NETLINK_ADD_MEMBERSHIP,
+            group as libc::c_int,
+        )
+    }
+
+    /// Group numbers the socket is subscribed to
+    /// (`NETLINK_LIST_MEMBERSHIPS`), in ascending order.
+    pub fn memberships(&self) -> Result<Vec<u32>> {
+        let mut bitmap = vec![0u32; 1];
+        loop {
+            let mut length = mem::size_of_val(bitmap.as_slice()) as libc::socklen_t;
+            let result = unsafe {
+                libc::getsockopt(
+                    self.descriptor,
+                    libc::SOL_NETLINK,
+                    libc::NETLINK_LIST_MEMBERSHIPS,
+                    bitmap.as_mut_ptr() as *mut libc::c_void,
+                    &mut length,
+                )
+            };
+            if result == -1 {
+                return Err(Error::last_os_error());
+            }
+
+            // The kernel reports the size needed for all groups.
+            let needed = (length as usize).div_ceil(mem::size_of::<u32>());
+            if needed <= bitmap.len() {
+                break;
+            }
+            bitmap.resize(needed, 0);
+        }
+
+        Ok(bitmap
+            .iter()
+            .enumerate()
+            .flat_map(|(word, bits)| {
+                (0..32)
+                    .filter(move |bit| bits & (1 << bit) != 0)
+                    .map(move |bit| (word * 32 + bit + 1) as u32)
+            })
+            .collect())
+    }
+
+    /// Report the multicast group number of received messages
+    /// (`NETLINK_PKTINFO`) in [`SocketAddress::group`], see
+    /// [`NetlinkSocket::recv_from`].
+    pub fn set_pktinfo(&self, enabled: bool) -> Result<()> {
+        self.set_option(
+            libc::SOL_NETLINK,
+            libc::NETLINK_PKTINFO,
+            enabled as libc::c_int,
+        )
+    }
+
+    fn set_option(&self, level: i32, name: i32, value: libc::c_int) -> Result<()> {
+        let result = unsafe {
+            libc::setsockopt(
+                self.descriptor,
+                level,
+                name,
+                &value as *const libc::c_int as *const libc::c_void,
+                mem::size_of_val(&value) as libc::socklen_t,
+            )
+        };
+        if result == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    fn option(&self, level: i32, name: i32) -> Result<libc::c_int> {
+        let mut value: libc::c_int = 0;
+        let mut value_length = mem::size_of_val(&value) as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                self.descriptor,
+                level,
+                name,
+                &mut value as *mut libc::c_int as *mut libc::c_void,
+                &mut value_length,
+            )
+        };
+        if result == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(value)
+    }
+
+    /// Set the integer `SOL_NETLINK` option `name` (e.g.
+    /// `libc::NETLINK_CAP_ACK`) to `value`.
+    ///
+    /// Escape hatch for options without a wrapper: prefer the typed methods
+    /// (e.g. [`NetlinkSocket::add_membership`]) when available.
+    pub fn set_netlink_option(&self, name: i32, value: i32) -> Result<()> {
+        self.set_option(libc::SOL_NETLINK, name, value)
+    }
+
+    /// Get the integer `SOL_NETLINK` option `name`, see
+    /// [`NetlinkSocket::set_netlink_option`].
+    pub fn get_netlink_option(&self, name: i32) -> Result<i32> {
+        self.option(libc::SOL_NETLINK, name)
+    }
+
+    /// Set the socket send buffer size (`SO_SNDBUF`) to `bytes`.
+    ///
+    /// The kernel doubles the requested value to account for bookkeeping
+    /// overhead and caps it at `net.core.wmem_max`, so
+    /// [`NetlinkSocket::send_buffer_size`] will usually report twice the
+    /// amount requested here.
+    pub fn set_send_buffer_size(&self, bytes: usize) -> Result<()> {
+        self.set_option(libc::SOL_SOCKET, libc::SO_SNDBUF, buffer_size(bytes)?)
+    }
+
+    /// Get the socket send buffer size (`SO_SNDBUF`) negotiated with the
+    /// kernel.
+    pub fn send_buffer_size(&self) -> Result<usize> {
+        Ok(self.option(libc::SOL_SOCKET, libc::SO_SNDBUF)? as usize)
+    }
+
+    /// Set the socket receive buffer size (`SO_RCVBUF`) to `bytes`.
+    ///
+    /// Like [`NetlinkSocket::set_send_buffer_size`] the kernel doubles the
+    /// value, capped at `net.core.rmem_max`.
+    pub fn set_recv_buffer_size(&self, bytes: usize) -> Result<()> {
+        self.set_option(libc::SOL_SOCKET, libc::SO_RCVBUF, buffer_size(bytes)?)
+    }
+
+    /// Get the socket receive buffer size (`SO_RCVBUF`) negotiated with the
+    /// kernel.
+    pub fn recv_buffer_size(&self) -> Result<usize> {
+        Ok(self.option(libc::SOL_SOCKET, libc::SO_RCVBUF)? as usize)
+    }
+
+    /// Make receive calls fail with `WouldBlock` after waiting `timeout`
+    /// for data (`SO_RCVTIMEO`), `None` waits forever.
+    ///
+    /// The kernel has microsecond resolution: shorter timeouts wait one
+    /// microsecond instead of being truncated to zero (no timeout).
+    pub fn set_recv_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        if timeout == Some(Duration::ZERO) {
+            // Zero means no timeout for the kernel.
+            return Err(Error::new(ErrorKind::InvalidInput, "zero receive timeout"));
+        }
+
+        let timeout = timeout
+            .map(|timeout| timeout.max(Duration::from_micros(1)))
+            .unwrap_or_default();
+        let value = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        let result = unsafe {
+            libc::setsockopt(
+                self.descriptor,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &value as *const libc::timeval as *const libc::c_void,
+                mem::size_of_val(&value) as libc::socklen_t,
+            )
+        };
+        if result == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Recommended buffer size for [`NetlinkSocket::recv`] derived from the
+    /// socket receive buffer: the usable part of `SO_RCVBUF` (half of the
+    /// reported value, the rest is kernel bookkeeping).
+    ///
+    /// [`crate::message::NETLINK_MESSAGE_MAXIMUM_SIZE`] is enough for
+    /// most replies, but large dumps (e.g. many VFs or full routing tables)
+    /// may produce bigger datagrams: size the buffers with this value to
+    /// avoid truncation.
+    pub fn max_recv_size(&self) -> Result<usize> {
+        Ok(self.recv_buffer_size()? / 2)
+    }
+
+    /// Create a new owner of the same kernel socket by duplicating the
+    /// descriptor (`F_DUPFD_CLOEXEC`).
+    ///
+    /// Each owner closes its own descriptor when dropped, the kernel
+    /// socket stays open until the last one goes away. The trace callback
+    /// (see [`NetlinkSocket::set_trace`]) is not copied.
+    pub fn try_clone(&self) -> Result<NetlinkSocket> {
+        let descriptor = unsafe { libc::fcntl(self.descriptor, libc::F_DUPFD_CLOEXEC, 0) };
+        if descriptor == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(NetlinkSocket {
+            descriptor,
+            protocol: self.protocol,
+            trace: None,
+        })
+    }
+}
+
+/// Message exchange abstraction used by
+/// [`crate::connection::NetlinkConnection`], allowing it to run on
+/// something other than a [`NetlinkSocket`] (e.g. canned replies in tests).
+pub trait Transport {
+    /// Send the whole `buffer` as one datagram.
+    fn send(&self, buffer: &[u8]) -> Result<usize>;
+    /// Receive one datagram into `buffer`.
+    fn recv(&self, buffer: &mut [u8]) -> Result<usize>;
+}
+
+impl<T: Transport> Transport for &T {
+    fn send(&self, buffer: &[u8]) -> Result<usize> {
+        (**self).send(buffer)
+    }
+
+    fn recv(&self, buffer: &mut [u8]) -> Result<usize> {
+        (**self).recv(buffer)
+    }
+}
+
+impl Transport for NetlinkSocket {
+    fn send(&self, buffer: &[u8]) -> Result<usize> {
+        Ok(NetlinkSocket::send(self, buffer, 0)? as usize)
+    }
+
+    fn recv(&self, buffer: &mut [u8]) -> Result<usize> {
+        Ok(NetlinkSocket::recv(self, buffer, 0)? as usize)
+    }
+}
+
+impl Drop for NetlinkSocket {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.descriptor) };
+    }
 }
 
 #[cfg(test)]
@@ -148,13 +846,435 @@ mod socket_test {
 
     #[test]
     fn bind() {
-        match NetlinkSocket::bind(
+        assert!(NetlinkSocket::bind(
             NetlinkProtocol::Route,
-            0,
+            PortId::auto(),
             netlink_groups::IPV4_INTERFACE_ADDRESS | netlink_groups::IPV4_ROUTE,
-        ) {
-            Ok(_socket) => assert!(true),
-            Err(_error) => assert!(false),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn bind_with_options() {
+        let descriptor_flags =
+            |socket: &NetlinkSocket| unsafe { libc::fcntl(socket.descriptor, libc::F_GETFD) };
+        let status_flags =
+            |socket: &NetlinkSocket| unsafe { libc::fcntl(socket.descriptor, libc::F_GETFL) };
+
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        assert_ne!(descriptor_flags(&socket) & libc::FD_CLOEXEC, 0);
+        assert_eq!(status_flags(&socket) & libc::O_NONBLOCK, 0);
+
+        let options = BindOptions {
+            cloexec: false,
+            nonblocking: true,
+            ..Default::default()
+        };
+        let socket =
+            NetlinkSocket::bind_with_options(NetlinkProtocol::Route, PortId::auto(), 0, options)
+                .unwrap();
+        assert_eq!(descriptor_flags(&socket) & libc::FD_CLOEXEC, 0);
+        assert_ne!(status_flags(&socket) & libc::O_NONBLOCK, 0);
+    }
+
+    #[test]
+    fn bind_all_route() {
+        assert!(NetlinkSocket::bind(
+            NetlinkProtocol::Route,
+            PortId::auto(),
+            netlink_groups::ALL_ROUTE
+        )
+        .is_ok());
+        assert!(NetlinkSocket::bind_groups(
+            NetlinkProtocol::Route,
+            PortId::auto(),
+            &[
+                libc::RTNLGRP_LINK,
+                libc::RTNLGRP_NEXTHOP,
+                libc::RTNLGRP_STATS
+            ],
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn bind_groups_above_32() {
+        let group = libc::RTNLGRP_STATS;
+        let socket = NetlinkSocket::bind_groups(
+            NetlinkProtocol::Route,
+            PortId::auto(),
+            &[libc::RTNLGRP_LINK, group],
+        )
+        .unwrap();
+        // Joined as group 36, not as the bits of 36 (groups 3 and 6).
+        assert_eq!(
+            socket.memberships().unwrap(),
+            vec![libc::RTNLGRP_LINK, group]
+        );
+
+        let error = NetlinkSocket::bind_groups(NetlinkProtocol::Route, PortId::auto(), &[0])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn bind_raw() {
+        let options = BindOptions {
+            socket_type: SocketType::Raw,
+            ..Default::default()
+        };
+        let socket =
+            NetlinkSocket::bind_with_options(NetlinkProtocol::Route, PortId::auto(), 0, options)
+                .unwrap();
+        assert_eq!(
+            socket.option(libc::SOL_SOCKET, libc::SO_TYPE).unwrap(),
+            libc::SOCK_RAW
+        );
+
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        assert_eq!(
+            socket.option(libc::SOL_SOCKET, libc::SO_TYPE).unwrap(),
+            libc::SOCK_DGRAM
+        );
+    }
+
+    #[test]
+    fn port_id() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        let port_id = socket.port_id().unwrap();
+        assert_ne!(port_id, PortId::auto());
+        assert_ne!(port_id, PortId::kernel());
+    }
+
+    #[test]
+    fn send_buffer_size() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        let requested = 16384;
+        let maximum = std::fs::read_to_string("/proc/sys/net/core/wmem_max")
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok());
+
+        socket.set_send_buffer_size(requested).unwrap();
+        let negotiated = socket.send_buffer_size().unwrap();
+        match maximum {
+            Some(maximum) if requested <= maximum => assert_eq!(negotiated, 2 * requested),
+            _ => eprintln!("skipping: net.core.wmem_max below {requested} or unreadable"),
         }
+
+        let error = socket.set_send_buffer_size(1 << 32).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(socket.send_buffer_size().unwrap(), negotiated);
+    }
+
+    #[test]
+    fn netlink_option() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        assert_eq!(socket.get_netlink_option(libc::NETLINK_CAP_ACK).unwrap(), 0);
+
+        socket.set_netlink_option(libc::NETLINK_CAP_ACK, 1).unwrap();
+        assert_eq!(socket.get_netlink_option(libc::NETLINK_CAP_ACK).unwrap(), 1);
+
+        let error = socket.get_netlink_option(-1).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::ENOPROTOOPT));
+    }
+
+    #[test]
+    fn recv_timeout() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        socket
+            .set_recv_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+
+        let mut buffer = [0u8; 64];
+        let error = socket.recv(&mut buffer, 0).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WouldBlock);
+
+        // Not truncated to zero, which would block forever.
+        socket
+            .set_recv_timeout(Some(Duration::from_nanos(500)))
+            .unwrap();
+        let error = socket.recv(&mut buffer, 0).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WouldBlock);
+
+        let error = socket.set_recv_timeout(Some(Duration::ZERO)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(socket.set_recv_timeout(None).is_ok());
+    }
+
+    #[test]
+    fn max_recv_size() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+
+        socket.set_recv_buffer_size(32768).unwrap();
+        assert_eq!(socket.max_recv_size().unwrap(), 32768);
+        socket.set_recv_buffer_size(65536).unwrap();
+        assert_eq!(socket.max_recv_size().unwrap(), 65536);
+
+        let error = socket.set_recv_buffer_size(1 << 32).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(socket.max_recv_size().unwrap(), 65536);
+    }
+
+    #[test]
+    fn bind_in_netns() {
+        use std::os::unix::fs::MetadataExt;
+
+        // Create a namespace in a helper thread so the test thread is
+        // never moved out of the original namespace.
+        let netns = std::thread::spawn(|| {
+            if unsafe { libc::unshare(libc::CLONE_NEWNET) } == -1 {
+                return None;
+            }
+            File::open("/proc/thread-self/ns/net").ok()
+        })
+        .join()
+        .unwrap();
+        let Some(netns) = netns else {
+            eprintln!("skipping: unable to create network namespace");
+            return;
+        };
+
+        let current_netns = std::fs::metadata("/proc/thread-self/ns/net").unwrap();
+        assert!(NetlinkSocket::bind_in_netns(
+            netns.as_raw_fd(),
+            NetlinkProtocol::Route,
+            PortId::auto(),
+            netlink_groups::LINK
+        )
+        .is_ok());
+
+        // Assert we are back in the original namespace.
+        let restored_netns = std::fs::metadata("/proc/thread-self/ns/net").unwrap();
+        assert_eq!(current_netns.ino(), restored_netns.ino());
+    }
+
+    #[test]
+    fn trace() {
+        use crate::message::{route_builder::get_links_request, NETLINK_MESSAGE_MAXIMUM_SIZE};
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        let trace_events = events.clone();
+        socket.set_trace(Some(Box::new(move |direction, bytes| {
+            trace_events.lock().unwrap().push((direction, bytes.len()))
+        })));
+
+        let mut buffer = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+        let length = get_links_request().build().to_array(&mut buffer);
+        socket.send(&buffer[..length], 0).unwrap();
+        let bytes_read = socket.recv(&mut buffer, 0).unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                (Direction::Send, length),
+                (Direction::Receive, bytes_read as usize)
+            ]
+        );
+
+        socket.set_trace(None);
+        let length = get_links_request().build().to_array(&mut buffer);
+        socket.send(&buffer[..length], 0).unwrap();
+        assert_eq!(events.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn recv_from_group() {
+        // User mode protocol: any process may send to its groups.
+        let receiver =
+            NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0b1010).unwrap();
+        let sender = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+
+        let group = SocketAddress {
+            pid: PortId::kernel(),
+            groups: 0b1000,
+            ..Default::default()
+        };
+        // The message is delivered to the group, but there is no kernel
+        // side to receive the unicast copy addressed to port ID zero.
+        let error = sender.send_to(b"notification", 0, &group).unwrap_err();
+        assert_eq!(error.raw_os_error(), Some(libc::ECONNREFUSED));
+
+        let mut buffer = [0u8; 64];
+        let (bytes_read, address) = receiver.recv_from(&mut buffer, 0).unwrap();
+        assert_eq!(&buffer[..bytes_read as usize], b"notification");
+        assert_eq!(address.groups, 0b1000);
+        assert!(!address.pid.is_kernel());
+    }
+
+    #[test]
+    fn recv_from_pktinfo() {
+        let receiver = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        receiver.add_membership(3).unwrap();
+        let sender = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        let group = SocketAddress {
+            pid: PortId::kernel(),
+            groups: 0b100,
+            ..Default::default()
+        };
+
+        let mut buffer = [0u8; 64];
+        for enabled in [true, false] {
+            receiver.set_pktinfo(enabled).unwrap();
+            // See `recv_from_group` for the expected error.
+            assert!(sender.send_to(b"pktinfo", 0, &group).is_err());
+
+            let (bytes_read, address) = receiver.recv_from(&mut buffer, 0).unwrap();
+            assert_eq!(&buffer[..bytes_read as usize], b"pktinfo");
+            assert_eq!(address.groups, 0b100);
+            assert_eq!(address.group, if enabled { 3 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn recv_raw() {
+        use crate::message::NETLINK_MESSAGE_MAXIMUM_SIZE;
+
+        let receiver = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0b1).unwrap();
+        let sender = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        let group = SocketAddress {
+            pid: PortId::kernel(),
+            groups: 1,
+            ..Default::default()
+        };
+
+        // Notification larger than the usual receive buffer.
+        let length = NETLINK_MESSAGE_MAXIMUM_SIZE + 16;
+        let mut notification = vec![0u8; length];
+        notification[..4].copy_from_slice(&(length as u32).to_ne_bytes());
+        // See `recv_from_group` for the expected error.
+        assert!(sender.send_to(&notification, 0, &group).is_err());
+
+        let bytes = receiver.recv_raw().unwrap();
+        assert_eq!(bytes.len(), length);
+        let header_length = u32::from_ne_bytes(bytes[..4].try_into().unwrap());
+        assert_eq!(header_length as usize, bytes.len());
+    }
+
+    #[test]
+    fn try_clone() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0b1).unwrap();
+        let clone = socket.try_clone().unwrap();
+        assert_ne!(clone.descriptor, socket.descriptor);
+        assert_ne!(
+            unsafe { libc::fcntl(clone.descriptor, libc::F_GETFD) } & libc::FD_CLOEXEC,
+            0
+        );
+        drop(socket);
+
+        let sender = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        let group = SocketAddress {
+            pid: PortId::kernel(),
+            groups: 1,
+            ..Default::default()
+        };
+        // See `recv_from_group` for the expected error.
+        assert!(sender.send_to(b"clone", 0, &group).is_err());
+
+        let mut buffer = [0u8; 64];
+        let bytes_read = clone.recv(&mut buffer, 0).unwrap();
+        assert_eq!(&buffer[..bytes_read as usize], b"clone");
+    }
+
+    #[test]
+    fn recv_protocol_mismatch() {
+        let socket = NetlinkSocket::bind(NetlinkProtocol::Route, PortId::auto(), 0).unwrap();
+        assert_eq!(socket.protocol(), NetlinkProtocol::Route);
+
+        let mut buffer = [0u8; 64];
+        let error = socket.recv_generic(&mut buffer).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(matches!(
+            error.get_ref().unwrap().downcast_ref::<NetlinkError>(),
+            Some(NetlinkError::ProtocolMismatch {
+                expected: NetlinkProtocol::Generic,
+                bound: NetlinkProtocol::Route,
+            })
+        ));
+        assert_eq!(
+            error.to_string(),
+            "expected generic netlink messages, but socket is bound to route"
+        );
+    }
+
+    #[test]
+    fn recv_overrun() {
+        let options = BindOptions {
+            nonblocking: true,
+            ..Default::default()
+        };
+        let receiver = NetlinkSocket::bind_with_options(
+            NetlinkProtocol::UserSock,
+            PortId::auto(),
+            0b1,
+            options,
+        )
+        .unwrap();
+        // The kernel rounds up to its minimum buffer size.
+        receiver.set_recv_buffer_size(0).unwrap();
+
+        let sender = NetlinkSocket::bind(NetlinkProtocol::UserSock, PortId::auto(), 0).unwrap();
+        let group = SocketAddress {
+            pid: PortId::kernel(),
+            groups: 1,
+            ..Default::default()
+        };
+        for _ in 0..64 {
+            // See `recv_from_group` for the expected error.
+            let _ = sender.send_to(&[0u8; 1024], 0, &group);
+        }
+
+        // Queued messages are read before the error is reported.
+        let mut buffer = [0u8; 2048];
+        let error = loop {
+            match receiver.recv(&mut buffer, 0) {
+                Ok(_) => continue,
+                Err(error) => break error,
+            }
+        };
+        assert_ne!(
+            error.kind(),
+            ErrorKind::WouldBlock,
+            "queue drained without overrun"
+        );
+        assert_eq!(error.kind(), ErrorKind::Other, "{}", error);
+        assert!(matches!(
+            error
+                .get_ref()
+                .and_then(|error| error.downcast_ref::<NetlinkError>()),
+            Some(NetlinkError::Overrun)
+        ));
+    }
+
+    #[test]
+    fn bind_permission_denied() {
+        // Credentials are per thread for the kernel: drop privileges in a
+        // helper thread only.
+        let result = std::thread::spawn(|| {
+            if unsafe { libc::syscall(libc::SYS_setresuid, 65534, 65534, 65534) } == -1 {
+                return None;
+            }
+            Some(NetlinkSocket::bind(
+                NetlinkProtocol::Xfrm,
+                PortId::auto(),
+                1,
+            ))
+        })
+        .join()
+        .unwrap();
+
+        let error = match result {
+            Some(Err(error)) if error.kind() == ErrorKind::PermissionDenied => error,
+            _ => {
+                eprintln!("skipping: unable to run unprivileged");
+                return;
+            }
+        };
+        assert!(matches!(
+            error.get_ref().and_then(|error| error.downcast_ref()),
+            Some(NetlinkError::PermissionDenied { groups: 1 })
+        ));
     }
 }
@@ -0,0 +1,157 @@
+// Copyright (c) 2024 Rafael Zalamena
+//
+// Redistribution and use in source and binary forms, with or without
+// modification, are permitted provided that the following conditions
+// are met:
+// 1. Redistributions of source code must retain the above copyright
+//    notice, this list of conditions and the following disclaimer.
+// 2. Redistributions in binary form must reproduce the above copyright
+//    notice, this list of conditions and the following disclaimer in the
+//    documentation and/or other materials provided with the distribution.
+//
+// THIS SOFTWARE IS PROVIDED BY THE AUTHOR AND CONTRIBUTORS ``AS IS'' AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+// IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+// ARE DISCLAIMED.  IN NO EVENT SHALL THE AUTHOR OR CONTRIBUTORS BE LIABLE
+// FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+// DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS
+// OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION)
+// HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT
+// LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY
+// OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF
+// SUCH DAMAGE.
+
+//! Serialized messages compared byte by byte against the requests sent
+//! by iproute2 (6.1.0, little endian host) for the equivalent `ip`
+//! command, as the connection sends them (including `NLM_F_ACK` for
+//! [`NetlinkConnection::execute`] requests).
+//!
+//! The files in `tests/golden` are the last `sendmsg` payload of the `ip`
+//! command (`strace -e trace=sendmsg -e write=3 ip ...` in a fresh
+//! namespace with `unshare -n`) with the sequence number zeroed, since
+//! `ip` derives it from the time. Replace a file only with a new capture,
+//! never with the crate output: running the tests with
+//! `NETLINK_GOLDEN_CAPTURE=1` (as root, with `strace` installed) captures
+//! the files again before comparing.
+//!
+//! [`NetlinkConnection::execute`]: netlink_rs::connection::NetlinkConnection::execute
+
+use netlink_rs::message::route_builder::{get_links_request, AddressBuilder, RouteBuilder};
+use netlink_rs::message::{NetlinkMessage, NETLINK_MESSAGE_MAXIMUM_SIZE};
+use std::env;
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Capture the last message `ip` sends on its netlink socket from the
+/// `strace` hex dump, with the sequence number zeroed.
+fn capture(ip_arguments: &[&str]) -> Vec<u8> {
+    let output = Command::new("unshare")
+        .args(["-n", "strace", "-e", "trace=sendmsg", "-e", "write=3", "ip"])
+        .args(ip_arguments)
+        .output()
+        .unwrap_or_else(|error| panic!("unshare: {}", error));
+    // ip fails in the empty namespace for some requests (e.g. unknown
+    // next hop), only the request sent matters.
+    let trace = String::from_utf8_lossy(&output.stderr);
+
+    let mut messages: Vec<Vec<u8>> = vec![];
+    for line in trace.lines() {
+        if line.starts_with("sendmsg(3,") {
+            messages.push(vec![]);
+        } else if let (Some(dump), Some(message)) = (line.strip_prefix(" | "), messages.last_mut())
+        {
+            // " | 00000  28 00 00 00 12 00 01 03  f2 b7 d0 6a 00 00 00 00  (..........j.... |"
+            let hex = dump
+                .get(7..56)
+                .unwrap_or_else(|| panic!("hex dump line: {}", line));
+            message.extend(
+                hex.split_whitespace()
+                    .map(|byte| u8::from_str_radix(byte, 16).unwrap()),
+            );
+        }
+    }
+
+    let mut message = messages
+        .pop()
+        .unwrap_or_else(|| panic!("ip {:?} sent nothing:\n{}", ip_arguments, trace));
+    message[8..12].fill(0);
+    message
+}
+
+fn check_golden(name: &str, ip_arguments: &[&str], mut message: NetlinkMessage, flags: i32) {
+    message.header.flags |= flags as u16;
+    let mut bytes = [0u8; NETLINK_MESSAGE_MAXIMUM_SIZE];
+    let length = message.to_array(&mut bytes);
+    let bytes = &bytes[..length];
+
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if env::var_os("NETLINK_GOLDEN_CAPTURE").is_some() {
+        fs::write(&path, capture(ip_arguments))
+            .unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+    }
+    let golden = fs::read(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+    assert_eq!(bytes, golden.as_slice(), "{} differs", name);
+}
+
+/// `ip link show`
+#[test]
+fn get_links() {
+    check_golden(
+        "get_links.bin",
+        &["link", "show"],
+        get_links_request()
+            .ext_mask(libc::RTEXT_FILTER_VF as u32)
+            .build(),
+        0,
+    );
+}
+
+/// `ip address add 192.0.2.10/24 dev lo`
+#[test]
+fn add_address() {
+    check_golden(
+        "add_address.bin",
+        &["address", "add", "192.0.2.10/24", "dev", "lo"],
+        AddressBuilder::add_address(1, IpAddr::from([192, 0, 2, 10]), 24).build(),
+        libc::NLM_F_ACK,
+    );
+}
+
+/// `ip route add 198.51.100.0/24 via 192.0.2.1 dev lo`
+#[test]
+fn add_route() {
+    check_golden(
+        "add_route.bin",
+        &[
+            "route",
+            "add",
+            "198.51.100.0/24",
+            "via",
+            "192.0.2.1",
+            "dev",
+            "lo",
+        ],
+        RouteBuilder::add_route(IpAddr::from([198, 51, 100, 0]), 24)
+            .gateway(IpAddr::from([192, 0, 2, 1]))
+            .oif(1)
+            .build(),
+        libc::NLM_F_ACK,
+    );
+}
+
+/// `ip -6 route add 2001:db8::/32 nhid 10`
+#[test]
+fn add_route_nexthop_id() {
+    check_golden(
+        "add_route_nexthop_id.bin",
+        &["-6", "route", "add", "2001:db8::/32", "nhid", "10"],
+        RouteBuilder::add_route("2001:db8::".parse().unwrap(), 32)
+            .nexthop_id(10)
+            .build(),
+        libc::NLM_F_ACK,
+    );
+}